@@ -0,0 +1,127 @@
+use std::collections::VecDeque;
+
+/// Occurrence of a pattern at `start..end`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Aho–Corasick automaton which finds all occurrences of several patterns in one pass,
+/// including overlapping ones
+#[derive(Debug, Clone)]
+pub struct Automaton {
+    /// Next state for every state and byte, with the failure links already resolved
+    transitions: Vec<[usize; 256]>,
+    /// Patterns ending in every state, including the ones reached through failure links
+    outputs: Vec<Vec<usize>>,
+    lengths: Vec<usize>,
+}
+
+impl Automaton {
+    pub fn new<P: AsRef<[u8]>>(patterns: &[P]) -> Self {
+        let mut transitions = vec![[0; 256]];
+        let mut outputs = vec![Vec::new()];
+        // Build the trie, using 0 for missing transitions as the root can't be a child
+        for (i, pattern) in patterns.iter().enumerate() {
+            let mut state = 0;
+            for &byte in pattern.as_ref() {
+                if transitions[state][byte as usize] == 0 {
+                    transitions.push([0; 256]);
+                    outputs.push(Vec::new());
+                    transitions[state][byte as usize] = transitions.len() - 1;
+                }
+                state = transitions[state][byte as usize];
+            }
+            outputs[state].push(i);
+        }
+        // Resolve the failure links breadth first, so the links of shorter prefixes are done
+        let mut failures = vec![0; transitions.len()];
+        let mut queue: VecDeque<_> = transitions[0].iter().copied().filter(|s| *s != 0).collect();
+        while let Some(state) = queue.pop_front() {
+            let failure = failures[state];
+            let inherited = outputs[failure].clone();
+            outputs[state].extend(inherited);
+            let failure_transitions = transitions[failure];
+            for (next, failure_next) in transitions[state].iter_mut().zip(failure_transitions) {
+                if *next == 0 {
+                    *next = failure_next;
+                } else {
+                    failures[*next] = failure_next;
+                    queue.push_back(*next);
+                }
+            }
+        }
+        Self {
+            transitions,
+            outputs,
+            lengths: patterns.iter().map(|p| p.as_ref().len()).collect(),
+        }
+    }
+
+    /// Iterate over all matches, ordered by their end
+    pub fn find_iter<'a, H>(&'a self, haystack: H) -> impl Iterator<Item = Match> + 'a
+    where
+        H: IntoIterator<Item = u8>,
+        H::IntoIter: 'a,
+    {
+        haystack
+            .into_iter()
+            .enumerate()
+            .scan(0, move |state, (i, byte)| {
+                *state = self.transitions[*state][byte as usize];
+                Some((i + 1, *state))
+            })
+            .flat_map(move |(end, state)| {
+                self.outputs[state].iter().map(move |&pattern| Match {
+                    pattern,
+                    start: end - self.lengths[pattern],
+                    end,
+                })
+            })
+    }
+
+    /// Find the match starting first, preferring the longest one if several start there.
+    ///
+    /// Stops reading the haystack as soon as no better match is possible.
+    pub fn find_leftmost(&self, haystack: impl IntoIterator<Item = u8>) -> Option<Match> {
+        let max_len = self.lengths.iter().copied().max().unwrap_or(0);
+        let mut best: Option<Match> = None;
+        for m in self.find_iter(haystack) {
+            if best.is_some_and(|best| m.end > best.start + max_len) {
+                break;
+            }
+            let is_better = best.is_none_or(|best| (m.start, best.end) < (best.start, m.end));
+            if is_better {
+                best = Some(m);
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_find_iter_overlapping() {
+        let automaton = Automaton::new(&["he", "she", "his", "hers"]);
+        let matches: Vec<_> = automaton
+            .find_iter("ushers".bytes())
+            .map(|m| (m.pattern, m.start, m.end))
+            .collect();
+        assert_eq!(matches, [(1, 1, 4), (0, 2, 4), (3, 2, 6)]);
+    }
+
+    #[test]
+    fn test_find_leftmost() {
+        let automaton = Automaton::new(&["teen", "eighteen", "eight"]);
+        let m = automaton.find_leftmost("xeighteen".bytes()).unwrap();
+        assert_eq!((m.pattern, m.start, m.end), (1, 1, 9));
+        assert_eq!(automaton.find_leftmost("nothing".bytes()), None);
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
-use utils::read_input_file_as_string;
+use day_1::{calibrate, Notation};
+use utils::{is_debugging, read_input_file_as_string};
 
 fn main() -> Result<()> {
     let input = read_input_file_as_string().context("Cannot read input")?;
@@ -10,23 +11,18 @@ fn main() -> Result<()> {
 }
 
 fn solve_problem(input: &str) -> Result<u32> {
-    let mut result: u32 = 0;
-    for line in input.trim().split('\n') {
-        let mut numbers = Vec::<u32>::new();
-        for char in line.chars() {
-            if let Some(digit) = char.to_digit(10) {
-                numbers.push(digit);
-            }
+    let calibrations = calibrate(input, Notation::Digits)?;
+    if is_debugging() {
+        for calibration in &calibrations {
+            println!("{calibration}");
         }
-        let first = numbers.first().context("No first number")?;
-        let last = numbers.last().context("No last number")?;
-        result += first * 10 + last;
     }
-    Ok(result)
+    Ok(calibrations.iter().map(|c| c.value).sum())
 }
 
 #[cfg(test)]
 mod tests {
+    use pretty_assertions::assert_eq;
     use std::fs::read_to_string;
 
     use super::*;
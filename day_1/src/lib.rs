@@ -0,0 +1,419 @@
+use std::{cmp::Reverse, collections::HashMap, error, fmt};
+
+use anyhow::{bail, Result};
+
+mod automaton;
+
+pub use automaton::*;
+
+const DIGIT_WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// Words which are decoded into values
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Vocabulary {
+    words: Vec<(String, u32)>,
+    /// Ignore the case of ASCII letters
+    ignore_case: bool,
+}
+
+impl Vocabulary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The numeric digits `0` to `9`
+    pub fn digits() -> Self {
+        (0..=9).fold(Self::new(), |vocabulary, n| {
+            vocabulary.with_word(&n.to_string(), n)
+        })
+    }
+
+    /// The numeric digits and the english words `one` to `nine`
+    pub fn english() -> Self {
+        DIGIT_WORDS
+            .iter()
+            .zip(1..)
+            .fold(Self::digits(), |vocabulary, (word, n)| {
+                vocabulary.with_word(word, n)
+            })
+    }
+
+    pub fn with_word(mut self, word: &str, value: u32) -> Self {
+        self.words.push((word.to_string(), value));
+        self
+    }
+
+    pub fn with_ignore_case(mut self, ignore_case: bool) -> Self {
+        self.ignore_case = ignore_case;
+        self
+    }
+
+    pub fn words(&self) -> &[(String, u32)] {
+        &self.words
+    }
+}
+
+/// How the digits are written in the calibration document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notation {
+    Digits,
+    DigitsAndWords,
+}
+
+impl Notation {
+    pub fn vocabulary(self) -> Vocabulary {
+        match self {
+            Self::Digits => Vocabulary::digits(),
+            Self::DigitsAndWords => Vocabulary::english(),
+        }
+    }
+}
+
+/// Which matches of the vocabulary are decoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchMode {
+    /// Only the first and the last match, which may overlap each other
+    FirstLast,
+    /// Every match, including overlapping ones
+    All,
+    /// Non overlapping matches, preferring the longest word if several start at the same position
+    LeftmostLongest,
+}
+
+/// Word of the vocabulary found at `start..end` of a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub start: usize,
+    pub end: usize,
+    pub value: u32,
+}
+
+/// Decodes calibration values from lines using a vocabulary
+#[derive(Debug, Clone)]
+pub struct Decoder {
+    forward: Automaton,
+    /// Matches the reversed words, to scan lines from the end
+    backward: Automaton,
+    values: Vec<u32>,
+    ignore_case: bool,
+    mode: MatchMode,
+}
+
+impl Decoder {
+    pub fn new(vocabulary: &Vocabulary, mode: MatchMode) -> Result<Self> {
+        let mut values_by_word = HashMap::new();
+        let mut words = Vec::new();
+        let mut values = Vec::new();
+        for (word, value) in &vocabulary.words {
+            let word = if vocabulary.ignore_case {
+                word.to_ascii_lowercase()
+            } else {
+                word.clone()
+            };
+            if word.is_empty() {
+                bail!("The vocabulary contains an empty word");
+            }
+            match values_by_word.insert(word.clone(), *value) {
+                Some(other) if other != *value => {
+                    bail!("The word '{word}' has the values {other} and {value}")
+                }
+                Some(_) => continue,
+                None => {}
+            }
+            words.push(word);
+            values.push(*value);
+        }
+        let reversed: Vec<Vec<u8>> = words
+            .iter()
+            .map(|word| word.bytes().rev().collect())
+            .collect();
+        Ok(Self {
+            forward: Automaton::new(&words),
+            backward: Automaton::new(&reversed),
+            values,
+            ignore_case: vocabulary.ignore_case,
+            mode,
+        })
+    }
+
+    fn bytes<'a>(&self, line: &'a str) -> impl DoubleEndedIterator<Item = u8> + 'a {
+        let ignore_case = self.ignore_case;
+        line.bytes().map(move |byte| {
+            if ignore_case {
+                byte.to_ascii_lowercase()
+            } else {
+                byte
+            }
+        })
+    }
+
+    fn token(&self, m: Match, offset: usize) -> Token {
+        Token {
+            start: offset + m.start,
+            end: offset + m.end,
+            value: self.values[m.pattern],
+        }
+    }
+
+    pub fn first(&self, line: &str) -> Option<Token> {
+        let m = self.forward.find_leftmost(self.bytes(line))?;
+        Some(self.token(m, 0))
+    }
+
+    pub fn last(&self, line: &str) -> Option<Token> {
+        let m = self.backward.find_leftmost(self.bytes(line).rev())?;
+        Some(Token {
+            start: line.len() - m.end,
+            end: line.len() - m.start,
+            value: self.values[m.pattern],
+        })
+    }
+
+    /// The matches counting according to the [`MatchMode`], ordered by their position
+    pub fn tokens(&self, line: &str) -> Vec<Token> {
+        match self.mode {
+            MatchMode::FirstLast => {
+                let (Some(first), Some(last)) = (self.first(line), self.last(line)) else {
+                    return Vec::new();
+                };
+                if first == last {
+                    vec![first]
+                } else {
+                    vec![first, last]
+                }
+            }
+            MatchMode::All => {
+                let mut tokens: Vec<_> = self
+                    .forward
+                    .find_iter(self.bytes(line))
+                    .map(|m| self.token(m, 0))
+                    .collect();
+                tokens.sort_by_key(|token| (token.start, Reverse(token.end)));
+                tokens
+            }
+            MatchMode::LeftmostLongest => {
+                let mut tokens = Vec::new();
+                let mut offset = 0;
+                while let Some(m) = self.forward.find_leftmost(self.bytes(&line[offset..])) {
+                    let token = self.token(m, offset);
+                    offset = token.end;
+                    tokens.push(token);
+                }
+                tokens
+            }
+        }
+    }
+
+    /// Decode the first and last token of a line, which is numbered `line_number` in the result
+    pub fn calibrate_line<'a>(
+        &self,
+        line_number: usize,
+        line: &'a str,
+    ) -> Result<LineCalibration<'a>, NoDigitError> {
+        let tokens = self.tokens(line);
+        let (Some(first), Some(last)) = (tokens.first(), tokens.last()) else {
+            return Err(NoDigitError {
+                line_number,
+                line: line.to_string(),
+            });
+        };
+        Ok(LineCalibration {
+            line_number,
+            line,
+            first: *first,
+            last: *last,
+            value: leading_digit(first.value) * 10 + last.value % 10,
+        })
+    }
+
+    /// Combine the leading digit of the first token with the trailing digit of the last token
+    pub fn calibration_value(&self, line: &str) -> Option<u32> {
+        self.calibrate_line(0, line).ok().map(|c| c.value)
+    }
+}
+
+/// Decode every non-blank line of the calibration document
+pub fn calibrate(input: &str, notation: Notation) -> Result<Vec<LineCalibration<'_>>> {
+    let decoder = Decoder::new(&notation.vocabulary(), MatchMode::FirstLast)?;
+    let calibrations = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| decoder.calibrate_line(i + 1, line))
+        .collect::<Result<_, _>>()?;
+    Ok(calibrations)
+}
+
+/// How a single line contributes to the calibration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineCalibration<'a> {
+    /// Starting at 1
+    pub line_number: usize,
+    pub line: &'a str,
+    pub first: Token,
+    pub last: Token,
+    pub value: u32,
+}
+
+impl fmt::Display for LineCalibration<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = |token: &Token| &self.line[token.start..token.end];
+        write!(
+            f,
+            "Line {}: '{}' reads '{}' first and '{}' last, which is {}",
+            self.line_number,
+            self.line,
+            text(&self.first),
+            text(&self.last),
+            self.value
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoDigitError {
+    /// Starting at 1
+    pub line_number: usize,
+    pub line: String,
+}
+
+impl fmt::Display for NoDigitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Line {} contains no digit: '{}'",
+            self.line_number, self.line
+        )
+    }
+}
+
+impl error::Error for NoDigitError {}
+
+fn leading_digit(mut value: u32) -> u32 {
+    while value >= 10 {
+        value /= 10;
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn values(tokens: &[Token]) -> Vec<u32> {
+        tokens.iter().map(|token| token.value).collect()
+    }
+
+    #[test]
+    fn test_first_and_last() {
+        let decoder = Decoder::new(&Vocabulary::english(), MatchMode::FirstLast).unwrap();
+        let first_and_last = |line| {
+            (
+                decoder.first(line).map(|t| t.value),
+                decoder.last(line).map(|t| t.value),
+            )
+        };
+        assert_eq!(first_and_last("sevenine"), (Some(7), Some(9)));
+        assert_eq!(first_and_last("xtwone3four"), (Some(2), Some(4)));
+        assert_eq!(first_and_last("zoneight234"), (Some(1), Some(4)));
+        assert_eq!(first_and_last("eighthree"), (Some(8), Some(3)));
+        assert_eq!(first_and_last("treb7uchet"), (Some(7), Some(7)));
+        assert_eq!(first_and_last("nothing"), (None, None));
+        assert_eq!(
+            decoder.last("xtwone3"),
+            Some(Token {
+                start: 6,
+                end: 7,
+                value: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_match_modes() {
+        let vocabulary = Vocabulary::english();
+        let tokens = |mode| {
+            let decoder = Decoder::new(&vocabulary, mode).unwrap();
+            values(&decoder.tokens("xtwone3eightwo"))
+        };
+        assert_eq!(tokens(MatchMode::FirstLast), [2, 2]);
+        assert_eq!(tokens(MatchMode::All), [2, 1, 3, 8, 2]);
+        assert_eq!(tokens(MatchMode::LeftmostLongest), [2, 3, 8]);
+
+        let decoder = Decoder::new(&vocabulary, MatchMode::LeftmostLongest).unwrap();
+        assert_eq!(decoder.calibration_value("xtwone3eightwo"), Some(28));
+        assert_eq!(decoder.calibration_value("twone"), Some(22));
+    }
+
+    #[test]
+    fn test_custom_vocabulary() {
+        let german = Vocabulary::digits()
+            .with_word("eins", 1)
+            .with_word("zwei", 2)
+            .with_word("drei", 3)
+            .with_word("null", 0)
+            .with_ignore_case(true);
+        let decoder = Decoder::new(&german, MatchMode::FirstLast).unwrap();
+        assert_eq!(decoder.calibration_value("xZweiNULLy"), Some(20));
+        assert_eq!(decoder.calibration_value("ONE"), None);
+
+        let teens = Vocabulary::english()
+            .with_word("ten", 10)
+            .with_word("eighteen", 18)
+            .with_word("seventeen", 17);
+        let decoder = Decoder::new(&teens, MatchMode::LeftmostLongest).unwrap();
+        assert_eq!(values(&decoder.tokens("eighteenten")), [18, 10]);
+        assert_eq!(decoder.calibration_value("seventeenx"), Some(17));
+        assert_eq!(decoder.calibration_value("3ten"), Some(30));
+    }
+
+    #[test]
+    fn test_calibrate() {
+        let input = "two1nine\n\nxtwone3four\n";
+        let calibrations = calibrate(input, Notation::DigitsAndWords).unwrap();
+        let summary: Vec<_> = calibrations
+            .iter()
+            .map(|c| (c.line_number, c.value))
+            .collect();
+        assert_eq!(summary, [(1, 29), (3, 24)]);
+        assert_eq!(
+            calibrations[1].to_string(),
+            "Line 3: 'xtwone3four' reads 'two' first and 'four' last, which is 24"
+        );
+
+        let calibrations = calibrate(input, Notation::Digits).unwrap();
+        assert_eq!(
+            calibrations[1].to_string(),
+            "Line 3: 'xtwone3four' reads '3' first and '3' last, which is 33"
+        );
+    }
+
+    #[test]
+    fn test_calibrate_without_digit() {
+        let err = calibrate("1abc2\npqr3stu8vwx\ntwo", Notation::Digits).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<NoDigitError>(),
+            Some(&NoDigitError {
+                line_number: 3,
+                line: "two".into()
+            })
+        );
+        assert_eq!(err.to_string(), "Line 3 contains no digit: 'two'");
+    }
+
+    #[test]
+    fn test_invalid_vocabulary() {
+        let vocabulary = Vocabulary::new()
+            .with_word("one", 1)
+            .with_word("ONE", 2)
+            .with_ignore_case(true);
+        let err = Decoder::new(&vocabulary, MatchMode::All).unwrap_err();
+        assert_eq!(err.to_string(), "The word 'one' has the values 1 and 2");
+        let err = Decoder::new(&Vocabulary::new().with_word("", 1), MatchMode::All).unwrap_err();
+        assert_eq!(err.to_string(), "The vocabulary contains an empty word");
+    }
+}
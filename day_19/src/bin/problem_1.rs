@@ -1,4 +1,4 @@
-use std::{cmp::Ordering, collections::HashMap};
+use std::{cell::RefCell, cmp::Ordering};
 
 use anyhow::{Context, Result};
 use nom::{
@@ -6,11 +6,12 @@ use nom::{
     bytes::complete::tag,
     character::complete::alpha1,
     combinator::{all_consuming, map},
-    sequence::tuple,
+    sequence::{separated_pair, tuple},
 };
 use utils::{
+    intern::{Id, Interner},
     parsing::{
-        self, d_curly, l0_comma, l1_comma, l1_newline, p_column, p_comma, p_eq, p_mspace,
+        self, blank_lines, d_curly, l0_comma, l1_comma, l1_newline, p_column, p_comma, p_eq,
         parse_with_nom,
     },
     read_input_file_as_string,
@@ -29,7 +30,7 @@ fn solve_problem(input: &str) -> Result<i64> {
     let mut result = 0;
     for part in &problem.parts {
         if problem.is_accepted(part) {
-            result += part.ratings.values().sum::<i64>()
+            result += part.ratings.iter().flatten().sum::<i64>()
         }
     }
     Ok(result)
@@ -37,14 +38,9 @@ fn solve_problem(input: &str) -> Result<i64> {
 
 impl Problem {
     fn is_accepted(&self, part: &Part) -> bool {
-        let mut current_workflow_name = WorkflowName("in".into());
+        let mut current_workflow_name = self.start;
         loop {
-            let workflow = self
-                .workflows
-                .get(&current_workflow_name)
-                .unwrap_or_else(|| {
-                    panic!("Workflow with name {current_workflow_name:?} not found.")
-                });
+            let workflow = &self.workflows[current_workflow_name.0.index()];
             match workflow.apply(part) {
                 RuleOutcome::Accept => return true,
                 RuleOutcome::Reject => return false,
@@ -65,7 +61,7 @@ impl Workflow {
 
 impl WorkflowRule {
     fn is_applicable(&self, part: &Part) -> bool {
-        let Some(rating) = part.ratings.get(&self.rating_name) else {
+        let Some(Some(rating)) = part.ratings.get(self.rating_name.0.index()) else {
             return false;
         };
         rating.cmp(&self.n) == self.expected_ord
@@ -74,7 +70,9 @@ impl WorkflowRule {
 
 #[derive(Debug, Clone)]
 struct Problem {
-    pub workflows: HashMap<WorkflowName, Workflow>,
+    /// Indexed by [`WorkflowName`]
+    pub workflows: Vec<Workflow>,
+    pub start: WorkflowName,
     pub parts: Vec<Part>,
 }
 
@@ -102,18 +100,27 @@ struct WorkflowRule {
 
 #[derive(Debug, Clone)]
 struct Part {
-    pub ratings: HashMap<RatingName, i64>,
+    /// Indexed by [`RatingName`]
+    pub ratings: Vec<Option<i64>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct WorkflowName(String);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct WorkflowName(Id);
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct RatingName(String);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct RatingName(Id);
 
 fn parse(input: &str) -> Result<Problem> {
-    let parse_rating_name = || map(alpha1, |s: &str| RatingName(s.into()));
-    let parse_workflow_name = || map(alpha1, |s: &str| WorkflowName(s.into()));
+    let workflow_names = RefCell::new(Interner::new());
+    let rating_names = RefCell::new(Interner::new());
+    // Only intern names of rules and ratings which were parsed completely, because a failed
+    // rule is retried as the fallback outcome of its workflow
+    let intern_rating_name = |s| RatingName(rating_names.borrow_mut().intern(s));
+    let parse_workflow_name = || {
+        map(alpha1, |s| {
+            WorkflowName(workflow_names.borrow_mut().intern(s))
+        })
+    };
     let parse_ordering = || {
         alt((
             map(tag("<"), |_| Ordering::Less),
@@ -130,13 +137,13 @@ fn parse(input: &str) -> Result<Problem> {
     let parse_rule = || {
         map(
             tuple((
-                parse_rating_name(),
+                alpha1,
                 parse_ordering(),
                 parsing::number,
                 p_column(parse_rule_outcome()),
             )),
-            |(part, expected_ord, n, outcome)| WorkflowRule {
-                rating_name: part,
+            |(name, expected_ord, n, outcome)| WorkflowRule {
+                rating_name: intern_rating_name(name),
                 expected_ord,
                 n,
                 outcome,
@@ -160,30 +167,45 @@ fn parse(input: &str) -> Result<Problem> {
         )
     };
     let parse_part = || {
-        map(
-            d_curly(l1_comma(tuple((
-                parse_rating_name(),
-                p_eq(parsing::number),
-            )))),
-            |ratings| Part {
-                ratings: ratings.into_iter().collect(),
-            },
-        )
+        d_curly(l1_comma(map(
+            tuple((alpha1, p_eq(parsing::number))),
+            |(name, rating)| (intern_rating_name(name), rating),
+        )))
     };
     let parse_problem = || {
-        map(
-            tuple((
-                l1_newline(parse_workflow()),
-                p_mspace(l1_newline(parse_part())),
-            )),
-            |(workflows, parts)| Problem {
-                workflows: workflows.into_iter().map(|w| (w.name.clone(), w)).collect(),
-                parts,
-            },
+        separated_pair(
+            l1_newline(parse_workflow()),
+            blank_lines,
+            l1_newline(parse_part()),
         )
     };
-    let problem = parse_with_nom(input.trim(), all_consuming(parse_problem()))?;
-    Ok(problem)
+    let (workflows, parts) = parse_with_nom(input.trim(), all_consuming(parse_problem()))?;
+
+    let workflow_names = workflow_names.into_inner();
+    let rating_names = rating_names.into_inner();
+    let start = workflow_names
+        .get("in")
+        .context("Workflow 'in' not found")?;
+    let workflows = workflow_names
+        .table(workflows.into_iter().map(|w| (w.name.0, w)))
+        .context("Every referenced workflow needs to be defined exactly once")?;
+    let parts = parts
+        .into_iter()
+        .map(|ratings| {
+            let mut part = Part {
+                ratings: vec![None; rating_names.len()],
+            };
+            for (name, rating) in ratings {
+                part.ratings[name.0.index()] = Some(rating);
+            }
+            part
+        })
+        .collect();
+    Ok(Problem {
+        workflows,
+        start: WorkflowName(start),
+        parts,
+    })
 }
 
 #[cfg(test)]
@@ -199,4 +221,22 @@ mod tests {
         let result = solve_problem(&input).unwrap();
         assert_eq!(result, 19114);
     }
+
+    #[test]
+    fn test_fallback_is_not_a_rating_name() {
+        let input = read_to_string("./example.txt").unwrap();
+
+        let problem = parse(&input).unwrap();
+        assert!(problem.parts.iter().all(|part| part.ratings.len() == 4));
+    }
+
+    #[test]
+    fn test_example_crlf() {
+        let input = read_to_string("./example.txt")
+            .unwrap()
+            .replace('\n', "\r\n");
+
+        let result = solve_problem(&input).unwrap();
+        assert_eq!(result, 19114);
+    }
 }
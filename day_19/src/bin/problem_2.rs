@@ -1,16 +1,18 @@
-use std::{collections::HashMap, ops::RangeInclusive};
+use std::cell::RefCell;
 
 use anyhow::{Context, Result};
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{alpha1, multispace0},
+    character::complete::alpha1,
     combinator::{all_consuming, map},
     multi::{separated_list0, separated_list1},
-    sequence::{delimited, preceded, tuple},
+    sequence::{delimited, preceded, separated_pair, tuple},
 };
 use utils::{
-    parsing::{self, parse_with_nom},
+    intern::{Id, Interner},
+    interval::Interval,
+    parsing::{self, blank_lines, l1_newline, parse_with_nom},
     read_input_file_as_string,
 };
 
@@ -32,16 +34,9 @@ impl Algorithmn {
     fn init(problem: Problem) -> Self {
         let initial_queue_item = QueueItem {
             range: PartRange {
-                ratings: [
-                    (RatingName::new("x"), 1..=4000),
-                    (RatingName::new("m"), 1..=4000),
-                    (RatingName::new("a"), 1..=4000),
-                    (RatingName::new("s"), 1..=4000),
-                ]
-                .into_iter()
-                .collect(),
+                ratings: vec![Interval::inclusive(1, 4000); RATING_NAMES.len()],
             },
-            next_workflow: WorkflowName::new("in"),
+            next_workflow: problem.start,
         };
         Self {
             problem,
@@ -52,25 +47,25 @@ impl Algorithmn {
 
     fn solve(&mut self) -> i64 {
         while let Some(item) = self.queue.pop() {
-            let workflow = self.problem.get_workflow(&item.next_workflow).clone();
+            let workflow = self.problem.get_workflow(item.next_workflow).clone();
             let mut fallback_range = item.range.clone();
             for rule in &workflow.rules {
+                let Some(rating_range) = fallback_range.ratings.get(rule.rating_name.0.index())
+                else {
+                    continue;
+                };
+                // Split the range into the part matching the rule and the fallback
+                let (matching, fallback) = match rule.op {
+                    RuleOperation::Less => rating_range.split_at(rule.n),
+                    RuleOperation::Greater => {
+                        let (below, above) = rating_range.split_at(rule.n + 1);
+                        (above, below)
+                    }
+                };
                 let mut next_range = fallback_range.clone();
-                // Restrict the range by the rule
-                if let Some(rating_range) = next_range.ratings.get_mut(&rule.rating_name) {
-                    *rating_range = match rule.op {
-                        RuleOperation::Less => *rating_range.start()..=(rule.n - 1),
-                        RuleOperation::Greater => (rule.n + 1)..=*rating_range.end(),
-                    };
-                    self.handle_rule_outcome(next_range, rule.outcome.clone());
-                }
-                // Invert the rule for the fallback range
-                if let Some(rating_range) = fallback_range.ratings.get_mut(&rule.rating_name) {
-                    *rating_range = match rule.op {
-                        RuleOperation::Less => rule.n..=*rating_range.end(),
-                        RuleOperation::Greater => *rating_range.start()..=rule.n,
-                    };
-                }
+                next_range.ratings[rule.rating_name.0.index()] = matching;
+                fallback_range.ratings[rule.rating_name.0.index()] = fallback;
+                self.handle_rule_outcome(next_range, rule.outcome.clone());
             }
             self.handle_rule_outcome(fallback_range, workflow.fallback.clone());
         }
@@ -90,31 +85,14 @@ impl Algorithmn {
 }
 
 impl Problem {
-    fn get_workflow(&self, name: &WorkflowName) -> &Workflow {
-        self.workflows
-            .get(name)
-            .unwrap_or_else(|| panic!("Workflow with {name:?} not found."))
+    fn get_workflow(&self, name: WorkflowName) -> &Workflow {
+        &self.workflows[name.0.index()]
     }
 }
 
 impl PartRange {
     fn score(&self) -> i64 {
-        self.ratings
-            .values()
-            .map(|r| r.end() - r.start() + 1)
-            .product()
-    }
-}
-
-impl RatingName {
-    fn new(name: impl Into<String>) -> Self {
-        Self(name.into())
-    }
-}
-
-impl WorkflowName {
-    fn new(name: impl Into<String>) -> Self {
-        Self(name.into())
+        self.ratings.iter().map(|r| r.len()).product()
     }
 }
 
@@ -133,12 +111,15 @@ struct QueueItem {
 
 #[derive(Debug, Clone)]
 struct PartRange {
-    pub ratings: HashMap<RatingName, RangeInclusive<i64>>,
+    /// Indexed by [`RatingName`]
+    pub ratings: Vec<Interval<i64>>,
 }
 
 #[derive(Debug, Clone)]
 struct Problem {
-    pub workflows: HashMap<WorkflowName, Workflow>,
+    /// Indexed by [`WorkflowName`]
+    pub workflows: Vec<Workflow>,
+    pub start: WorkflowName,
     #[allow(dead_code)]
     pub parts: Vec<Part>,
 }
@@ -174,18 +155,32 @@ enum RuleOperation {
 #[derive(Debug, Clone)]
 struct Part {
     #[allow(dead_code)]
-    pub ratings: HashMap<RatingName, i64>,
+    pub ratings: Vec<(RatingName, i64)>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct WorkflowName(String);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct WorkflowName(Id);
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct RatingName(String);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct RatingName(Id);
+
+/// The ratings every part has. They are interned first, so their ids are the indices in this array.
+const RATING_NAMES: [&str; 4] = ["x", "m", "a", "s"];
 
 fn parse(input: &str) -> Result<Problem> {
-    let parse_rating_name = || map(alpha1, |s: &str| RatingName(s.into()));
-    let parse_workflow_name = || map(alpha1, |s: &str| WorkflowName(s.into()));
+    let workflow_names = RefCell::new(Interner::new());
+    let rating_names = RefCell::new(Interner::new());
+    for name in RATING_NAMES {
+        rating_names.borrow_mut().intern(name);
+    }
+    // Only intern names of rules and ratings which were parsed completely, because a failed
+    // rule is retried as the fallback outcome of its workflow
+    let intern_rating_name = |s| RatingName(rating_names.borrow_mut().intern(s));
+    let parse_workflow_name = || {
+        map(alpha1, |s| {
+            WorkflowName(workflow_names.borrow_mut().intern(s))
+        })
+    };
     let parse_op = || {
         alt((
             map(tag("<"), |_| RuleOperation::Less),
@@ -202,13 +197,13 @@ fn parse(input: &str) -> Result<Problem> {
     let parse_rule = || {
         map(
             tuple((
-                parse_rating_name(),
+                alpha1,
                 parse_op(),
                 parsing::number,
                 preceded(tag(":"), parse_rule_outcome()),
             )),
-            |(part, op, n, outcome)| WorkflowRule {
-                rating_name: part,
+            |(name, op, n, outcome)| WorkflowRule {
+                rating_name: intern_rating_name(name),
                 op,
                 n,
                 outcome,
@@ -241,29 +236,37 @@ fn parse(input: &str) -> Result<Problem> {
                 tag("{"),
                 separated_list1(
                     tag(","),
-                    tuple((parse_rating_name(), preceded(tag("="), parsing::number))),
+                    map(
+                        tuple((alpha1, preceded(tag("="), parsing::number))),
+                        |(name, rating)| (intern_rating_name(name), rating),
+                    ),
                 ),
                 tag("}"),
             ),
-            |ratings| Part {
-                ratings: ratings.into_iter().collect(),
-            },
+            |ratings| Part { ratings },
         )
     };
     let parse_problem = || {
-        map(
-            tuple((
-                separated_list1(tag("\n"), parse_workflow()),
-                preceded(multispace0, separated_list1(tag("\n"), parse_part())),
-            )),
-            |(workflows, parts)| Problem {
-                workflows: workflows.into_iter().map(|w| (w.name.clone(), w)).collect(),
-                parts,
-            },
+        separated_pair(
+            l1_newline(parse_workflow()),
+            blank_lines,
+            l1_newline(parse_part()),
         )
     };
-    let problem = parse_with_nom(input.trim(), all_consuming(parse_problem()))?;
-    Ok(problem)
+    let (workflows, parts) = parse_with_nom(input.trim(), all_consuming(parse_problem()))?;
+
+    let workflow_names = workflow_names.into_inner();
+    let start = workflow_names
+        .get("in")
+        .context("Workflow 'in' not found")?;
+    let workflows = workflow_names
+        .table(workflows.into_iter().map(|w| (w.name.0, w)))
+        .context("Every referenced workflow needs to be defined exactly once")?;
+    Ok(Problem {
+        workflows,
+        start: WorkflowName(start),
+        parts,
+    })
 }
 
 #[cfg(test)]
@@ -279,4 +282,14 @@ mod tests {
         let result = solve_problem(&input).unwrap();
         assert_eq!(result, 167409079868000);
     }
+
+    #[test]
+    fn test_example_crlf() {
+        let input = read_to_string("./example.txt")
+            .unwrap()
+            .replace('\n', "\r\n");
+
+        let result = solve_problem(&input).unwrap();
+        assert_eq!(result, 167409079868000);
+    }
 }
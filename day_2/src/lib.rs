@@ -0,0 +1,408 @@
+use std::{cell::RefCell, collections::HashMap, error, fmt};
+
+use anyhow::Result;
+use nom::{
+    bytes::complete::tag,
+    character::complete::{alpha1, multispace1, space0},
+    combinator::{all_consuming, map},
+    multi::separated_list1,
+    sequence::{delimited, preceded, tuple},
+    IResult,
+};
+use utils::{
+    intern::{Id, Interner},
+    parsing::{self, parse_with_nom, Error},
+};
+
+/// The bag of the puzzle
+pub const DEFAULT_BAG: &str = "12 red, 13 green, 14 blue";
+
+#[derive(Debug, Clone)]
+pub struct Game {
+    pub id: usize,
+    pub rounds: Vec<Cubes>,
+}
+
+impl Game {
+    /// The fewest cubes of every color which make this game possible
+    pub fn minimum_bag(&self) -> Cubes {
+        self.rounds
+            .iter()
+            .fold(Cubes::default(), |bag, round| bag.max(round))
+    }
+
+    /// Check if every round could have been drawn from `bag`
+    pub fn is_possible_with(&self, bag: &Cubes) -> bool {
+        bag.contains(&self.minimum_bag())
+    }
+}
+
+/// A color drawn in a game which isn't in the bag at all
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingColor {
+    pub game: usize,
+    /// Starting at 1
+    pub round: usize,
+    pub color: String,
+}
+
+impl fmt::Display for MissingColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Game {}, round {}: '{}' is not in the bag",
+            self.game, self.round, self.color
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingColors(pub Vec<MissingColor>);
+
+impl fmt::Display for MissingColors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The games contain colors which are missing from the bag:"
+        )?;
+        for missing in &self.0 {
+            write!(f, "\n- {missing}")?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for MissingColors {}
+
+/// Make sure that every color drawn in the games is in the bag
+pub fn check_bag(games: &[Game], bag: &Cubes, colors: &Interner) -> Result<(), MissingColors> {
+    let mut missing = Vec::new();
+    for game in games {
+        for (i, round) in game.rounds.iter().enumerate() {
+            let mut round_colors: Vec<_> = round.counts.keys().copied().collect();
+            round_colors.sort();
+            for color in round_colors {
+                if !bag.counts.contains_key(&color) {
+                    missing.push(MissingColor {
+                        game: game.id,
+                        round: i + 1,
+                        color: colors.resolve(color.0).to_string(),
+                    });
+                }
+            }
+        }
+    }
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(MissingColors(missing))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cubes {
+    pub counts: HashMap<Color, Count>,
+}
+
+/// Interned name of a color
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Color(pub Id);
+
+/// Names of the colors, shared by the games and the bag
+pub type Colors<'a> = RefCell<Interner<'a>>;
+
+pub type Count = u32;
+
+impl Cubes {
+    pub fn new(counts: &[(Color, Count)]) -> Self {
+        Self {
+            counts: counts.iter().cloned().collect(),
+        }
+    }
+
+    /// The count of a color, which is 0 if it is missing
+    pub fn get(&self, color: &Color) -> Count {
+        self.counts.get(color).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> Count {
+        self.counts.values().sum()
+    }
+
+    /// The product of all counts
+    pub fn power(&self) -> Count {
+        self.counts.values().product()
+    }
+
+    /// The larger count of every color
+    pub fn max(&self, other: &Cubes) -> Cubes {
+        let mut counts = self.counts.clone();
+        for (color, count) in &other.counts {
+            let max = counts.entry(*color).or_insert(0);
+            *max = (*max).max(*count);
+        }
+        Cubes { counts }
+    }
+
+    /// List the counts like `12 red, 13 green`, ordered by when the colors were first seen
+    pub fn describe(&self, colors: &Interner) -> String {
+        let mut counts: Vec<_> = self.counts.iter().collect();
+        counts.sort();
+        let counts: Vec<_> = counts
+            .into_iter()
+            .map(|(color, count)| format!("{count} {}", colors.resolve(color.0)))
+            .collect();
+        counts.join(", ")
+    }
+
+    /// Check if there are at least as many cubes of every color as in `other`
+    pub fn contains(&self, other: &Cubes) -> bool {
+        other
+            .counts
+            .iter()
+            .all(|(color, count)| self.get(color) >= *count)
+    }
+}
+
+/// Find the bag with the fewest cubes which makes at least `n` of the games possible
+pub fn smallest_bag_for(games: &[Game], n: usize) -> Option<Cubes> {
+    if n > games.len() {
+        return None;
+    }
+    let needed: Vec<_> = games.iter().map(Game::minimum_bag).collect();
+    let mut colors: Vec<_> = needed
+        .iter()
+        .flat_map(|bag| bag.counts.keys().copied())
+        .collect();
+    colors.sort();
+    colors.dedup();
+    let mut search = SmallestBagSearch {
+        needed: &needed,
+        colors: &colors,
+        n,
+        chosen: Vec::new(),
+        best: None,
+    };
+    search.run(&(0..needed.len()).collect::<Vec<_>>(), 0);
+    let counts = search.best?.1;
+    Some(Cubes {
+        counts: colors.iter().copied().zip(counts).collect(),
+    })
+}
+
+/// Tries every count of a color that a game needs, one color after the other
+struct SmallestBagSearch<'a> {
+    needed: &'a [Cubes],
+    colors: &'a [Color],
+    n: usize,
+    chosen: Vec<Count>,
+    best: Option<(Count, Vec<Count>)>,
+}
+
+impl SmallestBagSearch<'_> {
+    /// `possible` are the games which are still possible with the chosen counts
+    fn run(&mut self, possible: &[usize], total: Count) {
+        if self.best.as_ref().is_some_and(|(best, _)| total >= *best) {
+            return;
+        }
+        let Some(color) = self.colors.get(self.chosen.len()) else {
+            self.best = Some((total, self.chosen.clone()));
+            return;
+        };
+        let mut counts: Vec<_> = possible
+            .iter()
+            .map(|game| self.needed[*game].get(color))
+            .chain([0])
+            .collect();
+        counts.sort_unstable();
+        counts.dedup();
+        let is_last_color = self.chosen.len() + 1 == self.colors.len();
+        for count in counts {
+            let still_possible: Vec<_> = possible
+                .iter()
+                .copied()
+                .filter(|game| self.needed[*game].get(color) <= count)
+                .collect();
+            if still_possible.len() < self.n {
+                continue;
+            }
+            self.chosen.push(count);
+            self.run(&still_possible, total + count);
+            self.chosen.pop();
+            // Larger counts of the last color only make the bag larger
+            if is_last_color {
+                break;
+            }
+        }
+    }
+}
+
+fn parse_cubes<'a, 'c>(
+    colors: &'c Colors<'a>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Cubes, Error<&'a str>> + 'c {
+    let parse_color = map(alpha1, |name| Color(colors.borrow_mut().intern(name)));
+    let parse_color_count = map(
+        delimited(
+            space0,
+            tuple((parsing::number, space0, parse_color)),
+            space0,
+        ),
+        |(n, _, color)| (color, n),
+    );
+    map(separated_list1(tag(","), parse_color_count), |counts| {
+        Cubes::new(&counts)
+    })
+}
+
+/// Parse cubes like `12 red, 13 green, 14 blue`
+pub fn parse_bag<'a>(input: &'a str, colors: &Colors<'a>) -> Result<Cubes> {
+    parse_with_nom(input.trim(), all_consuming(parse_cubes(colors)))
+}
+
+pub fn parse<'a>(input: &'a str, colors: &Colors<'a>) -> Result<Vec<Game>> {
+    let parse_game = map(
+        tuple((
+            preceded(tag("Game "), parsing::number),
+            tuple((tag(":"), multispace1)),
+            separated_list1(tag(";"), parse_cubes(colors)),
+        )),
+        |(id, _, rounds)| Game { id, rounds },
+    );
+    let parse_games = all_consuming(separated_list1(tag("\n"), parse_game));
+    parse_with_nom(input.trim(), parse_games)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read_to_string;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_minimum_bag() {
+        let input = read_to_string("./example.txt").unwrap();
+        let colors = Colors::default();
+        parse_bag(DEFAULT_BAG, &colors).unwrap();
+        let games = parse(&input, &colors).unwrap();
+
+        let minimum_bags: Vec<_> = games
+            .iter()
+            .map(|g| g.minimum_bag().describe(&colors.borrow()))
+            .collect();
+        assert_eq!(
+            minimum_bags,
+            [
+                "4 red, 2 green, 6 blue",
+                "1 red, 3 green, 4 blue",
+                "20 red, 13 green, 6 blue",
+                "14 red, 3 green, 15 blue",
+                "6 red, 3 green, 2 blue",
+            ]
+        );
+        assert_eq!(games[0].minimum_bag().power(), 48);
+    }
+
+    #[test]
+    fn test_is_possible_with() {
+        let input = read_to_string("./example.txt").unwrap();
+        let colors = Colors::default();
+        let bag = parse_bag(DEFAULT_BAG, &colors).unwrap();
+        let games = parse(&input, &colors).unwrap();
+
+        let possible: Vec<_> = games.iter().map(|g| g.is_possible_with(&bag)).collect();
+        assert_eq!(possible, [true, true, false, false, true]);
+        assert_eq!(check_bag(&games, &bag, &colors.borrow()), Ok(()));
+    }
+
+    #[test]
+    fn test_open_colors() {
+        let input = "Game 1: 2 teal, 1 red; 3 teal\nGame 2: 1 red, 1 mauve; 1 magenta";
+        let colors = Colors::default();
+        let bag = parse_bag("2 red, 3 teal", &colors).unwrap();
+        let games = parse(input, &colors).unwrap();
+
+        assert!(games[0].is_possible_with(&bag));
+        assert!(!games[1].is_possible_with(&bag));
+        let missing = check_bag(&games, &bag, &colors.borrow()).unwrap_err();
+        assert_eq!(
+            missing.0,
+            [
+                MissingColor {
+                    game: 2,
+                    round: 1,
+                    color: "mauve".into()
+                },
+                MissingColor {
+                    game: 2,
+                    round: 2,
+                    color: "magenta".into()
+                },
+            ]
+        );
+        assert_eq!(
+            missing.to_string(),
+            "The games contain colors which are missing from the bag:\n\
+             - Game 2, round 1: 'mauve' is not in the bag\n\
+             - Game 2, round 2: 'magenta' is not in the bag"
+        );
+    }
+
+    #[test]
+    fn test_smallest_bag_for() {
+        let input = read_to_string("./example.txt").unwrap();
+        let colors = Colors::default();
+        parse_bag(DEFAULT_BAG, &colors).unwrap();
+        let games = parse(&input, &colors).unwrap();
+
+        let smallest = |n| smallest_bag_for(&games, n).map(|bag| bag.describe(&colors.borrow()));
+        assert_eq!(smallest(0).as_deref(), Some("0 red, 0 green, 0 blue"));
+        assert_eq!(smallest(1).as_deref(), Some("1 red, 3 green, 4 blue"));
+        assert_eq!(smallest(3).as_deref(), Some("6 red, 3 green, 6 blue"));
+        assert_eq!(smallest(5).as_deref(), Some("20 red, 13 green, 15 blue"));
+        assert_eq!(smallest(6), None);
+    }
+
+    #[test]
+    fn test_smallest_bag_for_matches_brute_force() {
+        let mut interner = Interner::new();
+        let colors = ["red", "green", "blue"].map(|name| Color(interner.intern(name)));
+        // Pseudo random games with up to 7 cubes of every color
+        let mut seed = 12345_u32;
+        let mut next_count = || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16) % 8
+        };
+        let games: Vec<_> = (1..=8)
+            .map(|id| Game {
+                id,
+                rounds: vec![Cubes::new(&colors.map(|color| (color, next_count())))],
+            })
+            .collect();
+
+        for n in 0..=games.len() {
+            let mut best_total = None;
+            for counts in
+                (0..8).flat_map(|r| (0..8).flat_map(move |g| (0..8).map(move |b| [r, g, b])))
+            {
+                let bag = Cubes::new(&[
+                    (colors[0], counts[0]),
+                    (colors[1], counts[1]),
+                    (colors[2], counts[2]),
+                ]);
+                let possible = games.iter().filter(|g| g.is_possible_with(&bag)).count();
+                if possible >= n && best_total.is_none_or(|best| bag.total() < best) {
+                    best_total = Some(bag.total());
+                }
+            }
+            let smallest = smallest_bag_for(&games, n).unwrap();
+            assert_eq!(Some(smallest.total()), best_total, "n = {n}");
+            let possible = games
+                .iter()
+                .filter(|g| g.is_possible_with(&smallest))
+                .count();
+            assert!(possible >= n, "n = {n}");
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use anyhow::{bail, Context, Result};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{space0, space1},
+    combinator::{all_consuming, map},
+    multi::separated_list1,
+    sequence::{delimited, preceded, tuple},
+};
+use utils::{
+    bitset::BitSet128,
+    parsing::{self, parse_with_nom},
+};
+
+mod scratchcards;
+
+pub use scratchcards::*;
+
+#[derive(Debug, Clone)]
+pub struct Card {
+    pub id: usize,
+    pub winning_numbers: BitSet128,
+    pub my_numbers: BitSet128,
+}
+
+impl Card {
+    /// Fails if a number doesn't fit into a [`BitSet128`] or appears twice on the same side
+    pub fn new(id: usize, winning_numbers: &[usize], my_numbers: &[usize]) -> Result<Self> {
+        let to_set = |numbers: &[usize]| {
+            let mut set = BitSet128::new();
+            for n in numbers {
+                if *n >= BitSet128::CAPACITY {
+                    bail!(
+                        "Card {id}: {n} is out of range for the bitset of numbers below {}",
+                        BitSet128::CAPACITY
+                    );
+                }
+                if !set.insert(*n) {
+                    bail!("Card {id}: {n} appears twice");
+                }
+            }
+            Ok(set)
+        };
+        Ok(Self {
+            id,
+            winning_numbers: to_set(winning_numbers)?,
+            my_numbers: to_set(my_numbers)?,
+        })
+    }
+
+    /// How many of my numbers are winning numbers
+    pub fn matches(&self) -> usize {
+        self.winning_numbers.intersection_len(&self.my_numbers)
+    }
+
+    /// 1 point for the first match, doubled for every further match
+    pub fn points(&self) -> Result<usize> {
+        match self.matches() {
+            0 => Ok(0),
+            matches => 1_usize.checked_shl(matches as u32 - 1).with_context(|| {
+                format!(
+                    "Card {}: {matches} matches are worth too many points",
+                    self.id
+                )
+            }),
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<Card>> {
+    let parse_card = map(
+        tuple((
+            preceded(tuple((tag("Card"), space1)), parsing::number),
+            tuple((tag(":"), space1)),
+            separated_list1(space1, parsing::unsigned_number),
+            delimited(space0, tag("|"), space0),
+            separated_list1(space1, parsing::unsigned_number),
+        )),
+        |(id, _, winning_numbers, _, my_numbers)| (id, winning_numbers, my_numbers),
+    );
+    let parse_cards = all_consuming(separated_list1(tag("\n"), parse_card));
+    parse_with_nom(input.trim(), parse_cards)?
+        .into_iter()
+        .map(|(id, winning_numbers, my_numbers)| Card::new(id, &winning_numbers, &my_numbers))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read_to_string;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let input = read_to_string("./example.txt").unwrap();
+        let cards = parse(&input).unwrap();
+
+        let points: Vec<_> = cards.iter().map(|card| card.points().unwrap()).collect();
+        assert_eq!(points, [8, 2, 2, 1, 0, 0]);
+        assert_eq!(
+            (cards[0].winning_numbers & cards[0].my_numbers)
+                .iter()
+                .collect::<Vec<_>>(),
+            [17, 48, 83, 86]
+        );
+    }
+
+    #[test]
+    fn test_invalid_numbers() {
+        assert!(parse("Card 1: 1 2 | 3 127").is_ok());
+        assert_eq!(
+            parse("Card 1: 1 2 | 3 128").unwrap_err().to_string(),
+            "Card 1: 128 is out of range for the bitset of numbers below 128"
+        );
+        assert_eq!(
+            parse("Card 2: 1 2 | 3 7 3").unwrap_err().to_string(),
+            "Card 2: 3 appears twice"
+        );
+    }
+
+    #[test]
+    fn test_too_many_points() {
+        let numbers: Vec<_> = (0..65).collect();
+        let card = Card::new(1, &numbers, &numbers).unwrap();
+        assert_eq!(card.matches(), 65);
+        assert_eq!(
+            card.points().unwrap_err().to_string(),
+            "Card 1: 65 matches are worth too many points"
+        );
+        let card = Card::new(1, &numbers[..64], &numbers).unwrap();
+        assert_eq!(card.points().unwrap(), 1 << 63);
+    }
+}
@@ -0,0 +1,153 @@
+use std::{error, fmt};
+
+use crate::Card;
+
+/// Reason why the copies of the scratchcards can't be counted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CascadeError {
+    /// A card would award copies of cards after the last one
+    PastEnd {
+        card: usize,
+        matches: usize,
+        last_card: usize,
+    },
+    /// There are more copies of a card than fit into an `usize`
+    Overflow { card: usize },
+}
+
+impl fmt::Display for CascadeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PastEnd {
+                card,
+                matches,
+                last_card,
+            } => write!(
+                f,
+                "Card {card} has {matches} matches, which awards copies past the last card {last_card}"
+            ),
+            Self::Overflow { card } => write!(f, "Too many copies of card {card}"),
+        }
+    }
+}
+
+impl error::Error for CascadeError {}
+
+/// The matches of every card, computed once
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scratchcards {
+    pub ids: Vec<usize>,
+    pub matches: Vec<usize>,
+}
+
+impl Scratchcards {
+    pub fn new(cards: &[Card]) -> Self {
+        Self {
+            ids: cards.iter().map(|card| card.id).collect(),
+            matches: cards.iter().map(Card::matches).collect(),
+        }
+    }
+
+    /// How many instances of every card there are after winning all copies.
+    ///
+    /// Every card adds its instances to the next `matches` cards. These additions
+    /// are kept as a running sum and are removed again when they end.
+    pub fn copies(&self) -> Result<Vec<usize>, CascadeError> {
+        let n = self.matches.len();
+        let mut copies = Vec::with_capacity(n);
+        // `ending[i]` is the number of copies which are no longer won from card `i` on
+        let mut ending = vec![0_usize; n + 1];
+        let mut won = 0_usize;
+        for (i, matches) in self.matches.iter().copied().enumerate() {
+            let card = self.ids[i];
+            let overflow = || CascadeError::Overflow { card };
+            won -= ending[i];
+            let instances = won.checked_add(1).ok_or_else(overflow)?;
+            copies.push(instances);
+            if matches == 0 {
+                continue;
+            }
+            if i + matches >= n {
+                return Err(CascadeError::PastEnd {
+                    card,
+                    matches,
+                    last_card: self.ids[n - 1],
+                });
+            }
+            won = won.checked_add(instances).ok_or_else(overflow)?;
+            let end = &mut ending[i + matches + 1];
+            *end = end.checked_add(instances).ok_or_else(overflow)?;
+        }
+        Ok(copies)
+    }
+
+    /// The number of all cards including the copies
+    pub fn total(&self) -> Result<usize, CascadeError> {
+        let copies = self.copies()?;
+        copies
+            .iter()
+            .zip(&self.ids)
+            .try_fold(0_usize, |total, (copies, card)| {
+                total
+                    .checked_add(*copies)
+                    .ok_or(CascadeError::Overflow { card: *card })
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read_to_string;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::parse;
+
+    use super::*;
+
+    fn scratchcards(matches: &[usize]) -> Scratchcards {
+        Scratchcards {
+            ids: (1..=matches.len()).collect(),
+            matches: matches.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_example() {
+        let input = read_to_string("./example.txt").unwrap();
+        let scratchcards = Scratchcards::new(&parse(&input).unwrap());
+
+        assert_eq!(scratchcards.matches, [4, 2, 2, 1, 0, 0]);
+        assert_eq!(scratchcards.copies().unwrap(), [1, 2, 4, 8, 14, 1]);
+        assert_eq!(scratchcards.total().unwrap(), 30);
+    }
+
+    #[test]
+    fn test_past_end() {
+        assert_eq!(
+            scratchcards(&[1, 2, 0]).copies(),
+            Err(CascadeError::PastEnd {
+                card: 2,
+                matches: 2,
+                last_card: 3
+            })
+        );
+        assert_eq!(scratchcards(&[]).total(), Ok(0));
+    }
+
+    #[test]
+    fn test_overflow() {
+        // Every card doubles the instances of all following cards
+        let mut matches: Vec<_> = (1..=70).rev().collect();
+        matches.push(0);
+        let result = scratchcards(&matches).copies();
+        assert!(
+            matches!(result, Err(CascadeError::Overflow { .. })),
+            "{result:?}"
+        );
+
+        let mut matches: Vec<_> = (1..=10).rev().collect();
+        matches.push(0);
+        assert_eq!(scratchcards(&matches).copies().unwrap()[10], 1 << 10);
+    }
+}
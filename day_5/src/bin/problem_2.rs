@@ -1,17 +1,8 @@
-use std::ops::Range;
-
 use anyhow::{Context, Result};
-use nom::{
-    bytes::complete::{tag, take_until},
-    character::complete::{multispace1, space0, space1},
-    combinator::map,
-    multi::separated_list1,
-    sequence::{preceded, tuple},
-};
-use rayon::prelude::*;
+use day_5::{parse, Almanac};
 use utils::{
-    parsing::{self, parse_with_nom},
-    read_input_file_as_string,
+    interval::{Interval, IntervalSet},
+    is_debugging, read_input_file_as_string,
 };
 
 fn main() -> Result<()> {
@@ -23,126 +14,61 @@ fn main() -> Result<()> {
 }
 
 fn solve_problem(input: &str) -> Result<usize> {
-    let problem = parse(input)?;
-    let result = problem
-        .starting_seed_ranges
-        .par_iter()
-        .flat_map(|r| r.clone())
-        .map(|seed| {
-            let soil = problem.seed_to_soil_map.get_destination(&seed);
-            let fertilizer = problem.soil_to_fertilizer_map.get_destination(&soil);
-            let water = problem.fertilizer_to_water_map.get_destination(&fertilizer);
-            let light = problem.water_to_light_map.get_destination(&water);
-            let temperature = problem.light_to_temperature_map.get_destination(&light);
-            let humidity = problem
-                .temperature_to_humidity_map
-                .get_destination(&temperature);
-            problem.humidity_to_location_map.get_destination(&humidity)
-        })
-        .min()
-        .expect("No starting seeds found");
-    dbg!(&result);
-    Ok(result)
+    let almanac = parse(input)?;
+    let lowest = find_lowest_location(&almanac)?.context("No starting seeds found")?;
+    if is_debugging() {
+        println!(
+            "Seed {} ends up at the lowest location {}",
+            lowest.seed, lowest.location
+        );
+        let searched = search_lowest_location(&almanac)?;
+        println!("Binary searching the inverse maps finds {searched:?}");
+    }
+    Ok(lowest.location)
 }
 
-#[derive(Debug, Clone)]
-pub struct Problem {
-    pub starting_seed_ranges: Vec<Range<usize>>,
-    pub seed_to_soil_map: Map,
-    pub soil_to_fertilizer_map: Map,
-    pub fertilizer_to_water_map: Map,
-    pub water_to_light_map: Map,
-    pub light_to_temperature_map: Map,
-    pub temperature_to_humidity_map: Map,
-    pub humidity_to_location_map: Map,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LowestLocation {
+    pub seed: usize,
+    pub location: usize,
 }
 
-#[derive(Debug, Clone)]
-pub struct Map {
-    pub ranges: Vec<MapRange>,
+fn find_lowest_location(almanac: &Almanac) -> Result<Option<LowestLocation>> {
+    let map = almanac.map_between("seed", "location")?;
+    let lowest = almanac
+        .seed_ranges()?
+        .into_iter()
+        .flat_map(|seeds| map.map_interval(seeds))
+        .filter(|(seeds, _)| !seeds.is_empty())
+        .min_by_key(|(_, locations)| locations.start)
+        .map(|(seeds, locations)| LowestLocation {
+            seed: seeds.start,
+            location: locations.start,
+        });
+    Ok(lowest)
 }
 
-impl Map {
-    pub fn get_destination(&self, source: &usize) -> usize {
-        for range in &self.ranges {
-            if let Some(result) = range.get_destination(source) {
-                return result;
-            }
-        }
-        *source
+/// Find the lowest location by searching for the smallest `l` for which a seed ends up at `0..=l`
+fn search_lowest_location(almanac: &Almanac) -> Result<Option<usize>> {
+    let seeds: IntervalSet<usize> = almanac.seed_ranges()?.into_iter().collect();
+    let reaches_seeds = |max_location: usize| -> Result<bool> {
+        let locations = IntervalSet::from_iter([Interval::inclusive(0, max_location)]);
+        let sources = almanac.get_sources("seed", "location", &locations)?;
+        Ok(!sources.intersection(&seeds).is_empty())
+    };
+    let (mut low, mut high) = (0, usize::MAX - 1);
+    if !reaches_seeds(high)? {
+        return Ok(None);
     }
-}
-
-#[derive(Debug, Clone)]
-pub struct MapRange {
-    pub destination_range_start: usize,
-    pub source_range_start: usize,
-    pub range_length: usize,
-}
-
-impl MapRange {
-    pub fn get_destination(&self, source: &usize) -> Option<usize> {
-        if self.source_range().contains(source) {
-            let offset = source - self.source_range_start;
-            Some(self.destination_range_start + offset)
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if reaches_seeds(mid)? {
+            high = mid;
         } else {
-            None
+            low = mid + 1;
         }
     }
-
-    pub fn source_range(&self) -> Range<usize> {
-        self.source_range_start..(self.source_range_start + self.range_length)
-    }
-}
-
-fn parse(input: &str) -> Result<Problem> {
-    let parse_seed_seed_ranges = preceded(
-        tuple((tag("seeds:"), space0)),
-        separated_list1(
-            space1,
-            map(
-                tuple((parsing::number, space1, parsing::number)),
-                |(start, _, length): (usize, _, usize)| start..(start + length),
-            ),
-        ),
-    );
-    let parse_map_range = map(
-        tuple((
-            preceded(space0, parsing::number),
-            preceded(space0, parsing::number),
-            preceded(space0, parsing::number),
-        )),
-        |(destination_range_start, source_range_start, range_length)| MapRange {
-            destination_range_start,
-            source_range_start,
-            range_length,
-        },
-    );
-    let parse_map = map(
-        preceded(
-            tuple((take_until("map:"), tag("map:"), multispace1)),
-            separated_list1(multispace1, parse_map_range),
-        ),
-        |ranges| Map { ranges },
-    );
-    let parse_problem = map(
-        tuple((
-            parse_seed_seed_ranges,
-            separated_list1(multispace1, parse_map),
-        )),
-        |(starting_seed_ranges, maps)| Problem {
-            starting_seed_ranges,
-            seed_to_soil_map: maps[0].clone(),
-            soil_to_fertilizer_map: maps[1].clone(),
-            fertilizer_to_water_map: maps[2].clone(),
-            water_to_light_map: maps[3].clone(),
-            light_to_temperature_map: maps[4].clone(),
-            temperature_to_humidity_map: maps[5].clone(),
-            humidity_to_location_map: maps[6].clone(),
-        },
-    );
-    let problem = parse_with_nom(input.trim(), parse_problem)?;
-    Ok(problem)
+    Ok(Some(low))
 }
 
 #[cfg(test)]
@@ -156,6 +82,30 @@ mod tests {
         let input = read_to_string("./example.txt").unwrap();
 
         let result = solve_problem(&input).unwrap();
-        assert_eq!(result, 35);
+        assert_eq!(result, 46);
+    }
+
+    #[test]
+    fn test_lowest_location_seed() {
+        let input = read_to_string("./example.txt").unwrap();
+
+        let lowest = find_lowest_location(&parse(&input).unwrap()).unwrap();
+        assert_eq!(
+            lowest,
+            Some(LowestLocation {
+                seed: 82,
+                location: 46
+            })
+        );
+    }
+
+    #[test]
+    fn test_search_matches_forward_mapping() {
+        let input = read_to_string("./example.txt").unwrap();
+        let almanac = parse(&input).unwrap();
+
+        let lowest = find_lowest_location(&almanac).unwrap().unwrap();
+        let searched = search_lowest_location(&almanac).unwrap();
+        assert_eq!(searched, Some(lowest.location));
     }
 }
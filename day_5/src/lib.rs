@@ -0,0 +1,506 @@
+use anyhow::{bail, Context, Result};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{line_ending, space0, space1},
+    combinator::{all_consuming, map},
+    multi::separated_list1,
+    sequence::{preceded, separated_pair, tuple},
+};
+use utils::{
+    interval::{Interval, IntervalSet},
+    parsing::{self, blank_lines, named_sections, parse_with_nom},
+};
+
+mod validation;
+
+pub use validation::*;
+
+#[derive(Debug, Clone)]
+pub struct Almanac {
+    pub seeds: Vec<usize>,
+    /// Ordered so that every map starts at the destination category of the previous one
+    pub maps: Vec<Map>,
+}
+
+impl Almanac {
+    /// Interpret the seeds as pairs of start and length
+    pub fn seed_ranges(&self) -> Result<Vec<Interval<usize>>> {
+        if !self.seeds.len().is_multiple_of(2) {
+            bail!("Seed ranges need to consist of pairs of numbers");
+        }
+        self.seeds
+            .chunks(2)
+            .map(|pair| {
+                Interval::checked_from_len(pair[0], pair[1]).with_context(|| {
+                    format!(
+                        "The seed range starting at {} with length {} is too large",
+                        pair[0], pair[1]
+                    )
+                })
+            })
+            .collect()
+    }
+
+    pub fn first_category(&self) -> &str {
+        self.maps.first().map_or("seed", |m| &m.source)
+    }
+
+    pub fn last_category(&self) -> &str {
+        self.maps.last().map_or("seed", |m| &m.destination)
+    }
+
+    /// Get the maps leading from the `source` to the `destination` category
+    pub fn path(&self, source: &str, destination: &str) -> Result<&[Map]> {
+        let start = match self.maps.iter().position(|m| m.source == source) {
+            Some(start) => start,
+            None if source == destination && self.last_category() == source => self.maps.len(),
+            None => bail!("No map from category '{source}' found"),
+        };
+        if source == destination {
+            return Ok(&self.maps[start..start]);
+        }
+        let end = self.maps[start..]
+            .iter()
+            .position(|m| m.destination == destination)
+            .with_context(|| format!("No maps lead from '{source}' to '{destination}'"))?;
+        Ok(&self.maps[start..=start + end])
+    }
+
+    /// Compose all maps from the `source` to the `destination` category into a single one
+    pub fn map_between(&self, source: &str, destination: &str) -> Result<Map> {
+        let path = self.path(source, destination)?;
+        Ok(path
+            .iter()
+            .fold(Map::identity(source), |map, next| map.compose(next)))
+    }
+
+    /// Get all values of the `source` category which end up in `destinations`
+    pub fn get_sources(
+        &self,
+        source: &str,
+        destination: &str,
+        destinations: &IntervalSet<usize>,
+    ) -> Result<IntervalSet<usize>> {
+        let path = self.path(source, destination)?;
+        Ok(path
+            .iter()
+            .rev()
+            .fold(destinations.clone(), |values, map| map.get_sources(&values)))
+    }
+
+    /// Compose all maps into a single one from the first to the last category
+    pub fn full_map(&self) -> Map {
+        self.map_between(self.first_category(), self.last_category())
+            .expect("The chain is validated when parsing")
+    }
+}
+
+/// Piecewise mapping between two categories. Values outside of all ranges are mapped to themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Map {
+    pub source: String,
+    pub destination: String,
+    pub ranges: Vec<MapRange>,
+}
+
+impl Map {
+    pub fn identity(category: &str) -> Self {
+        Self {
+            source: category.into(),
+            destination: category.into(),
+            ranges: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        format!("{}-to-{}", self.source, self.destination)
+    }
+
+    pub fn get_destination(&self, source: &usize) -> usize {
+        for range in &self.ranges {
+            if let Some(result) = range.get_destination(source) {
+                return result;
+            }
+        }
+        *source
+    }
+
+    /// Split `source` at the boundaries of the map ranges and map every piece.
+    ///
+    /// Returns `(source, destination)` pairs.
+    pub fn map_interval(&self, source: Interval<usize>) -> Vec<(Interval<usize>, Interval<usize>)> {
+        let mut pieces = Vec::new();
+        let mut unmapped = IntervalSet::from_iter([source]);
+        for (range, source) in self.effective_source_ranges() {
+            for piece in unmapped.intersection(&source).iter() {
+                pieces.push((*piece, range.map_interval(*piece)));
+            }
+            unmapped = unmapped.difference(&source);
+        }
+        pieces.extend(unmapped.iter().map(|piece| (*piece, *piece)));
+        pieces
+    }
+
+    /// Map a set of source values to the set of their destinations
+    pub fn get_destinations(&self, sources: &IntervalSet<usize>) -> IntervalSet<usize> {
+        sources
+            .iter()
+            .flat_map(|source| self.map_interval(*source))
+            .map(|(_, destination)| destination)
+            .collect()
+    }
+
+    /// Get all source values which are mapped into `destinations`
+    pub fn get_sources(&self, destinations: &IntervalSet<usize>) -> IntervalSet<usize> {
+        let mut sources = Vec::new();
+        let mut mapped = IntervalSet::new();
+        for (range, source) in self.effective_source_ranges() {
+            for piece in source.iter() {
+                let destination = IntervalSet::from_iter([range.map_interval(*piece)]);
+                for hit in destinations.intersection(&destination).iter() {
+                    let offset = hit.start - range.destination_range_start;
+                    sources.push(Interval::from_len(
+                        range.source_range_start + offset,
+                        hit.len(),
+                    ));
+                }
+            }
+            mapped = mapped.union(&source);
+        }
+        // Values which aren't in any range are their own source
+        sources.extend(destinations.difference(&mapped).iter().copied());
+        sources.into_iter().collect()
+    }
+
+    /// Create a map which applies `self` first and `next` afterwards.
+    ///
+    /// The ranges of the result don't overlap and are sorted by their source.
+    pub fn compose(&self, next: &Map) -> Map {
+        let mut ranges = Vec::new();
+        let mut push_range = |source: Interval<usize>, destination: Interval<usize>| {
+            if source != destination {
+                ranges.push(MapRange {
+                    destination_range_start: destination.start,
+                    source_range_start: source.start,
+                    range_length: source.len(),
+                });
+            }
+        };
+        let mut mapped_by_self = IntervalSet::new();
+        for (range, source) in self.effective_source_ranges() {
+            for piece in source.iter() {
+                let destination = range.map_interval(*piece);
+                for (next_source, next_destination) in next.map_interval(destination) {
+                    let offset = next_source.start - destination.start;
+                    push_range(
+                        Interval::from_len(piece.start + offset, next_source.len()),
+                        next_destination,
+                    );
+                }
+            }
+            mapped_by_self = mapped_by_self.union(&source);
+        }
+        // Values outside of the ranges of `self` are passed to `next` unchanged
+        for (range, source) in next.effective_source_ranges() {
+            for piece in source.difference(&mapped_by_self).iter() {
+                push_range(*piece, range.map_interval(*piece));
+            }
+        }
+        ranges.sort_by_key(|r| r.source_range_start);
+        Map {
+            source: self.source.clone(),
+            destination: next.destination.clone(),
+            ranges,
+        }
+    }
+
+    /// The source values each range is actually used for, as earlier ranges take precedence
+    fn effective_source_ranges(&self) -> Vec<(&MapRange, IntervalSet<usize>)> {
+        let mut covered = IntervalSet::new();
+        self.ranges
+            .iter()
+            .map(|range| {
+                let source = IntervalSet::from_iter([range.source_range()]);
+                let effective = source.difference(&covered);
+                covered = covered.union(&source);
+                (range, effective)
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapRange {
+    pub destination_range_start: usize,
+    pub source_range_start: usize,
+    pub range_length: usize,
+}
+
+impl MapRange {
+    pub fn get_destination(&self, source: &usize) -> Option<usize> {
+        if self.source_range().contains(*source) {
+            let offset = source - self.source_range_start;
+            Some(self.destination_range_start + offset)
+        } else {
+            None
+        }
+    }
+
+    pub fn source_range(&self) -> Interval<usize> {
+        Interval::from_len(self.source_range_start, self.range_length)
+    }
+
+    /// Map a part of the source range
+    fn map_interval(&self, source: Interval<usize>) -> Interval<usize> {
+        let offset = source.start - self.source_range_start;
+        Interval::from_len(self.destination_range_start + offset, source.len())
+    }
+}
+
+pub fn parse(input: &str) -> Result<Almanac> {
+    let parse_seeds = preceded(
+        tuple((tag("seeds:"), space0)),
+        separated_list1(space1, parsing::number),
+    );
+    let parse_map_range = map(
+        tuple((
+            preceded(space0, parsing::number),
+            preceded(space0, parsing::number),
+            preceded(space0, parsing::number),
+        )),
+        |(destination_range_start, source_range_start, range_length)| MapRange {
+            destination_range_start,
+            source_range_start,
+            range_length,
+        },
+    );
+    let parse_problem = separated_pair(
+        parse_seeds,
+        blank_lines,
+        named_sections(separated_list1(line_ending, parse_map_range)),
+    );
+    let (seeds, sections) = parse_with_nom(input.trim(), all_consuming(parse_problem))?;
+    let maps = sections
+        .iter()
+        .map(|(name, ranges)| {
+            let (source, destination) = name
+                .strip_suffix(" map")
+                .and_then(|categories| categories.split_once("-to-"))
+                .with_context(|| format!("Invalid map name '{name}'"))?;
+            Ok(Map {
+                source: source.into(),
+                destination: destination.into(),
+                ranges: ranges.clone(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    validate_maps(&maps)?;
+    Ok(Almanac {
+        seeds,
+        maps: chain_maps(maps)?,
+    })
+}
+
+/// Order the maps so that each one continues at the destination of the previous one
+fn chain_maps(mut maps: Vec<Map>) -> Result<Vec<Map>> {
+    let Some(first) = maps
+        .iter()
+        .position(|m| !maps.iter().any(|other| other.destination == m.source))
+    else {
+        if maps.is_empty() {
+            return Ok(maps);
+        }
+        bail!("The maps contain a cycle");
+    };
+    let mut chain = vec![maps.remove(first)];
+    while !maps.is_empty() {
+        let category = &chain.last().unwrap().destination;
+        let next = maps
+            .iter()
+            .position(|m| &m.source == category)
+            .with_context(|| {
+                let unlinked: Vec<_> = maps.iter().map(|m| m.name()).collect();
+                format!(
+                    "The category '{category}' is not continued by any map, but these maps are unused: {}",
+                    unlinked.join(", ")
+                )
+            })?;
+        chain.push(maps.remove(next));
+    }
+    Ok(chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read_to_string;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn example() -> Almanac {
+        parse(&read_to_string("./example.txt").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_chain() {
+        let almanac = example();
+        let categories: Vec<_> = almanac.maps.iter().map(|m| m.source.as_str()).collect();
+        assert_eq!(
+            categories,
+            [
+                "seed",
+                "soil",
+                "fertilizer",
+                "water",
+                "light",
+                "temperature",
+                "humidity"
+            ]
+        );
+        assert_eq!(almanac.last_category(), "location");
+    }
+
+    #[test]
+    fn test_chain_with_shuffled_maps() {
+        let input = "seeds: 1\n\nb-to-c map:\n0 1 1\n\na-to-b map:\n5 0 2";
+        let almanac = parse(input).unwrap();
+        assert_eq!(almanac.first_category(), "a");
+        assert_eq!(
+            almanac.map_between("a", "c").unwrap().get_destination(&1),
+            6
+        );
+    }
+
+    #[test]
+    fn test_chain_errors() {
+        let gap = "seeds: 1\n\na-to-b map:\n0 1 1\n\nc-to-d map:\n5 0 2";
+        let err = parse(gap).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "The category 'b' is not continued by any map, but these maps are unused: c-to-d"
+        );
+
+        let cycle = "seeds: 1\n\na-to-b map:\n0 1 1\n\nb-to-a map:\n5 0 2";
+        assert_eq!(
+            parse(cycle).unwrap_err().to_string(),
+            "The maps contain a cycle"
+        );
+
+        let invalid = "seeds: 1\n\na-b map:\n0 1 1";
+        assert_eq!(
+            parse(invalid).unwrap_err().to_string(),
+            "Invalid map name 'a-b map'"
+        );
+    }
+
+    #[test]
+    fn test_seed_range_overflow() {
+        let almanac =
+            parse("seeds: 18446744073709551615 2\n\nseed-to-location map:\n0 1 1").unwrap();
+        assert_eq!(
+            almanac.seed_ranges().unwrap_err().to_string(),
+            "The seed range starting at 18446744073709551615 with length 2 is too large"
+        );
+    }
+
+    #[test]
+    fn test_map_between_any_categories() {
+        let almanac = example();
+        let map = almanac.map_between("soil", "humidity").unwrap();
+        // Soil 81 leads to fertilizer 81, water 81, light 74, temperature 78 and humidity 78
+        assert_eq!(map.get_destination(&81), 78);
+        assert!(almanac.map_between("humidity", "soil").is_err());
+        assert!(almanac.map_between("soil", "gold").is_err());
+        assert_eq!(almanac.map_between("light", "light").unwrap().ranges, []);
+    }
+
+    #[test]
+    fn test_full_map_matches_step_by_step_mapping() {
+        let almanac = example();
+        let full_map = almanac.full_map();
+        for seed in 0..200 {
+            let expected = almanac
+                .maps
+                .iter()
+                .fold(seed, |value, map| map.get_destination(&value));
+            assert_eq!(full_map.get_destination(&seed), expected, "seed {seed}");
+        }
+        let sources: Vec<_> = full_map.ranges.iter().map(|r| r.source_range()).collect();
+        for (a, b) in sources.iter().zip(sources.iter().skip(1)) {
+            assert!(a.end <= b.start, "{a:?} and {b:?} overlap");
+        }
+    }
+
+    #[test]
+    fn test_get_sources() {
+        let map = &example().maps[0];
+        let set = |intervals: &[Interval<usize>]| intervals.iter().copied().collect();
+        // 50 and 51 are sources of other values themselves, so only 98 and 99 map to them
+        assert_eq!(
+            map.get_sources(&set(&[Interval::new(50, 52)])),
+            set(&[Interval::new(98, 100)])
+        );
+        assert_eq!(
+            map.get_sources(&set(&[Interval::new(0, 10), Interval::new(99, 102)])),
+            set(&[
+                Interval::new(0, 10),
+                Interval::new(97, 98),
+                Interval::new(100, 102)
+            ])
+        );
+        assert_eq!(map.get_sources(&set(&[])), set(&[]));
+    }
+
+    #[test]
+    fn test_get_sources_is_inverse_of_full_map() {
+        let almanac = example();
+        let full_map = almanac.full_map();
+        let locations = IntervalSet::from_iter([Interval::new(0, 47)]);
+        let seeds = almanac.get_sources("seed", "location", &locations).unwrap();
+        assert!(seeds.contains(82));
+        for seed in 0..200 {
+            let location = full_map.get_destination(&seed);
+            assert_eq!(seeds.contains(seed), location <= 46, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_get_destinations() {
+        let map = Map {
+            source: "seed".into(),
+            destination: "soil".into(),
+            ranges: vec![
+                MapRange {
+                    destination_range_start: 50,
+                    source_range_start: 98,
+                    range_length: 2,
+                },
+                MapRange {
+                    destination_range_start: 52,
+                    source_range_start: 50,
+                    range_length: 48,
+                },
+            ],
+        };
+        // 45..50 and 120..130 pass through unmapped, 50..51 moves to 52..53, 60..70 to 62..72
+        // and 98..99 to 50..51
+        let sources: IntervalSet<usize> = [
+            Interval::new(45, 51),
+            Interval::new(60, 70),
+            Interval::new(98, 99),
+            Interval::new(120, 130),
+        ]
+        .into_iter()
+        .collect();
+        let expected: IntervalSet<usize> = [
+            Interval::new(45, 51),
+            Interval::new(52, 53),
+            Interval::new(62, 72),
+            Interval::new(120, 130),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(map.get_destinations(&sources), expected);
+    }
+}
@@ -0,0 +1,201 @@
+use std::{error, fmt};
+
+use crate::{Almanac, Map, MapRange};
+
+/// Problem in a map which would lead to wrong or ambiguous results
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// Two ranges share source values, so only the first one would be used for them
+    Overlap {
+        map: String,
+        first: MapRange,
+        second: MapRange,
+    },
+    EmptyRange {
+        map: String,
+        range: MapRange,
+    },
+    /// The end of the source or destination range doesn't fit into an `usize`
+    Overflow {
+        map: String,
+        range: MapRange,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overlap { map, first, second } => write!(
+                f,
+                "{map} map: The source ranges of '{first}' and '{second}' overlap"
+            ),
+            Self::EmptyRange { map, range } => write!(f, "{map} map: '{range}' is empty"),
+            Self::Overflow { map, range } => {
+                write!(f, "{map} map: The end of '{range}' is too large")
+            }
+        }
+    }
+}
+
+/// All problems found in an almanac
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationErrors(pub Vec<ValidationError>);
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The almanac is invalid:")?;
+        for error in &self.0 {
+            write!(f, "\n- {error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for ValidationErrors {}
+
+impl fmt::Display for MapRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.destination_range_start, self.source_range_start, self.range_length
+        )
+    }
+}
+
+impl Almanac {
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        validate_maps(&self.maps)
+    }
+}
+
+pub(crate) fn validate_maps(maps: &[Map]) -> Result<(), ValidationErrors> {
+    let errors: Vec<_> = maps.iter().flat_map(|m| m.validate()).collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationErrors(errors))
+    }
+}
+
+impl Map {
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let map = || self.name();
+        let mut errors = Vec::new();
+        let mut valid_ranges: Vec<&MapRange> = Vec::new();
+        for range in &self.ranges {
+            if range.range_length == 0 {
+                errors.push(ValidationError::EmptyRange {
+                    map: map(),
+                    range: range.clone(),
+                });
+                continue;
+            }
+            let source_end = range.source_range_start.checked_add(range.range_length);
+            let destination_end = range
+                .destination_range_start
+                .checked_add(range.range_length);
+            if source_end.is_none() || destination_end.is_none() {
+                errors.push(ValidationError::Overflow {
+                    map: map(),
+                    range: range.clone(),
+                });
+                continue;
+            }
+            for other in &valid_ranges {
+                if other.source_range().overlaps(&range.source_range()) {
+                    errors.push(ValidationError::Overlap {
+                        map: map(),
+                        first: (*other).clone(),
+                        second: range.clone(),
+                    });
+                }
+            }
+            valid_ranges.push(range);
+        }
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::parse;
+
+    use super::*;
+
+    fn range(destination: usize, source: usize, length: usize) -> MapRange {
+        MapRange {
+            destination_range_start: destination,
+            source_range_start: source,
+            range_length: length,
+        }
+    }
+
+    #[test]
+    fn test_valid_map() {
+        let map = Map {
+            source: "a".into(),
+            destination: "b".into(),
+            ranges: vec![range(50, 98, 2), range(52, 50, 48)],
+        };
+        assert_eq!(map.validate(), []);
+    }
+
+    #[test]
+    fn test_invalid_map() {
+        let map = Map {
+            source: "a".into(),
+            destination: "b".into(),
+            ranges: vec![
+                range(0, 10, 5),
+                range(100, 14, 2),
+                range(7, 7, 0),
+                range(usize::MAX, 0, 2),
+                range(0, usize::MAX - 1, 2),
+                range(200, 0, 11),
+            ],
+        };
+        assert_eq!(
+            map.validate(),
+            [
+                ValidationError::Overlap {
+                    map: "a-to-b".into(),
+                    first: range(0, 10, 5),
+                    second: range(100, 14, 2),
+                },
+                ValidationError::EmptyRange {
+                    map: "a-to-b".into(),
+                    range: range(7, 7, 0),
+                },
+                ValidationError::Overflow {
+                    map: "a-to-b".into(),
+                    range: range(usize::MAX, 0, 2),
+                },
+                ValidationError::Overflow {
+                    map: "a-to-b".into(),
+                    range: range(0, usize::MAX - 1, 2),
+                },
+                ValidationError::Overlap {
+                    map: "a-to-b".into(),
+                    first: range(0, 10, 5),
+                    second: range(200, 0, 11),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_almanac() {
+        let input = "seeds: 1\n\na-to-b map:\n0 10 5\n100 14 2\n\nb-to-c map:\n7 7 0";
+        let err = parse(input).unwrap_err();
+        assert_eq!(err.downcast_ref::<ValidationErrors>().unwrap().0.len(), 2);
+        assert_eq!(
+            err.to_string(),
+            "The almanac is invalid:\n\
+             - a-to-b map: The source ranges of '0 10 5' and '100 14 2' overlap\n\
+             - b-to-c map: '7 7 0' is empty"
+        );
+    }
+}
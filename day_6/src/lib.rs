@@ -0,0 +1,287 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::{Context, Result};
+use nom::combinator::all_consuming;
+use num_traits::PrimInt;
+use utils::{
+    interval::Interval,
+    isqrt,
+    parsing::{labelled_table, parse_with_nom, RowNumbers},
+};
+
+/// Parse the races from the `Time` and `Distance` rows
+pub fn parse<T: PrimInt + FromStr + fmt::Debug>(
+    input: &str,
+    row_numbers: RowNumbers,
+) -> Result<Vec<Race<T>>> {
+    let table = parse_with_nom(input.trim(), all_consuming(labelled_table(row_numbers)))?;
+    let time = table.position("Time").context("Missing 'Time' row")?;
+    let distance = table
+        .position("Distance")
+        .context("Missing 'Distance' row")?;
+    let races = table
+        .columns()
+        .iter()
+        .map(|column| Race {
+            time_ms: column[time],
+            min_distance_mm: column[distance],
+        })
+        .collect();
+    Ok(races)
+}
+
+/// Holding the button for `ms` milliseconds moves the boat `ms * (time_ms - ms)` millimeters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Race<T> {
+    pub time_ms: T,
+    pub min_distance_mm: T,
+}
+
+impl<T: PrimInt> Race<T> {
+    pub fn new(time_ms: T, min_distance_mm: T) -> Self {
+        Self {
+            time_ms,
+            min_distance_mm,
+        }
+    }
+
+    pub fn is_winning(&self, hold_ms: T) -> bool {
+        match hold_ms.checked_mul(&(self.time_ms - hold_ms)) {
+            Some(distance) => distance > self.min_distance_mm,
+            // The distance doesn't fit into `T`, so it has to be larger than the record
+            None => true,
+        }
+    }
+
+    /// All hold times in `1..time_ms` which beat the record
+    pub fn winning_hold_times(&self) -> Interval<T> {
+        let one = T::one();
+        let best = self.time_ms >> 1;
+        if best < one || !self.is_winning(best) {
+            return Interval::empty();
+        }
+        // The distances are symmetric around the best hold time
+        let first = self.first_winning_hold_time(best);
+        Interval::inclusive(first, self.time_ms - first)
+    }
+
+    pub fn count_wins(&self) -> T {
+        self.winning_hold_times().len()
+    }
+
+    /// The hold time which moves the boat the furthest. `time_ms - best_hold_time()` is as good.
+    pub fn best_hold_time(&self) -> T {
+        (self.time_ms >> 1).max(T::zero())
+    }
+
+    /// The furthest distance the boat can move, if it fits into `T`
+    pub fn max_distance(&self) -> Option<T> {
+        let best = self.best_hold_time();
+        best.checked_mul(&(self.time_ms - best).max(T::zero()))
+    }
+
+    pub fn analyze(&self) -> RaceAnalysis<T> {
+        RaceAnalysis {
+            race: *self,
+            winning_hold_times: self.winning_hold_times(),
+            wins: self.count_wins(),
+            best_hold_time_ms: self.best_hold_time(),
+            max_distance_mm: self.max_distance(),
+        }
+    }
+
+    /// Find the smaller root of `ms * (time_ms - ms) = min_distance_mm`, which is
+    /// `(time_ms - sqrt(time_ms^2 - 4 * min_distance_mm)) / 2`.
+    ///
+    /// `best` needs to be a winning hold time.
+    fn first_winning_hold_time(&self, best: T) -> T {
+        let one = T::one();
+        if self.min_distance_mm < T::zero() {
+            return one;
+        }
+        let Some(discriminant) = self.discriminant() else {
+            return self.search_first_winning_hold_time(best);
+        };
+        // The estimate is off by at most one because of the rounding
+        let mut first = ((self.time_ms - isqrt(discriminant)) >> 1).clamp(one, best);
+        while !self.is_winning(first) {
+            first = first + one;
+        }
+        while first > one && self.is_winning(first - one) {
+            first = first - one;
+        }
+        first
+    }
+
+    fn discriminant(&self) -> Option<T> {
+        let four = T::from(4)?;
+        self.time_ms
+            .checked_mul(&self.time_ms)?
+            .checked_sub(&four.checked_mul(&self.min_distance_mm)?)
+    }
+
+    /// Binary search for races which are too large to compute the discriminant
+    fn search_first_winning_hold_time(&self, best: T) -> T {
+        let (mut low, mut high) = (T::one(), best);
+        while low < high {
+            let mid = low + ((high - low) >> 1);
+            if self.is_winning(mid) {
+                high = mid;
+            } else {
+                low = mid + T::one();
+            }
+        }
+        low
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RaceAnalysis<T> {
+    pub race: Race<T>,
+    pub winning_hold_times: Interval<T>,
+    pub wins: T,
+    pub best_hold_time_ms: T,
+    /// `None` if the distance doesn't fit into `T`
+    pub max_distance_mm: Option<T>,
+}
+
+impl<T: PrimInt + fmt::Display> fmt::Display for RaceAnalysis<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Race {
+            time_ms,
+            min_distance_mm,
+        } = self.race;
+        write!(f, "{time_ms} ms with a record of {min_distance_mm} mm, ")?;
+        match self.max_distance_mm {
+            Some(distance) => write!(
+                f,
+                "holding {} ms reaches {distance} mm, ",
+                self.best_hold_time_ms
+            )?,
+            None => write!(
+                f,
+                "holding {} ms reaches the most, ",
+                self.best_hold_time_ms
+            )?,
+        }
+        match self.winning_hold_times.last() {
+            Some(last) => write!(
+                f,
+                "winning with {}..={last} ms ({} ways)",
+                self.winning_hold_times.start, self.wins
+            ),
+            None => write!(f, "impossible to win"),
+        }
+    }
+}
+
+/// Describe every race on its own line
+pub fn breakdown<T: PrimInt + fmt::Display>(races: &[Race<T>]) -> String {
+    races
+        .iter()
+        .enumerate()
+        .map(|(i, race)| format!("Race {}: {}", i + 1, race.analyze()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read_to_string;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn count_wins_by_scanning(race: &Race<i64>) -> i64 {
+        (1..race.time_ms).filter(|ms| race.is_winning(*ms)).count() as i64
+    }
+
+    #[test]
+    fn test_parse() {
+        let input = read_to_string("./example.txt").unwrap();
+
+        let races: Vec<Race<i32>> = parse(&input, RowNumbers::Separate).unwrap();
+        assert_eq!(
+            races,
+            [Race::new(7, 9), Race::new(15, 40), Race::new(30, 200)]
+        );
+        let races: Vec<Race<i64>> = parse(&input, RowNumbers::Joined).unwrap();
+        assert_eq!(races, [Race::new(71530, 940200)]);
+    }
+
+    #[test]
+    fn test_analyze() {
+        let analysis = Race::new(7, 9).analyze();
+        assert_eq!(
+            analysis,
+            RaceAnalysis {
+                race: Race::new(7, 9),
+                winning_hold_times: Interval::new(2, 6),
+                wins: 4,
+                best_hold_time_ms: 3,
+                max_distance_mm: Some(12),
+            }
+        );
+        assert_eq!(Race::new(i64::MAX, 0).max_distance(), None);
+    }
+
+    #[test]
+    fn test_breakdown() {
+        assert_eq!(
+            breakdown(&[Race::new(7, 9), Race::new(4, 4)]),
+            "Race 1: 7 ms with a record of 9 mm, holding 3 ms reaches 12 mm, winning with 2..=5 ms (4 ways)\n\
+             Race 2: 4 ms with a record of 4 mm, holding 2 ms reaches 4 mm, impossible to win"
+        );
+    }
+
+    #[test]
+    fn test_winning_hold_times() {
+        assert_eq!(Race::new(7, 9).winning_hold_times(), Interval::new(2, 6));
+        assert_eq!(Race::new(15, 40).winning_hold_times(), Interval::new(4, 12));
+        assert_eq!(
+            Race::new(30, 200).winning_hold_times(),
+            Interval::new(11, 20)
+        );
+        assert_eq!(Race::new(71530, 940200).count_wins(), 71503);
+    }
+
+    #[test]
+    fn test_count_wins_matches_scanning() {
+        for time_ms in -2..60 {
+            for min_distance_mm in -3..(time_ms * time_ms / 4 + 3) {
+                let race = Race::new(time_ms, min_distance_mm);
+                assert_eq!(race.count_wins(), count_wins_by_scanning(&race), "{race:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_exact_boundaries_for_large_races() {
+        let races = [
+            Race::new(i64::MAX, i64::MAX),
+            Race::new(3_037_000_499, 2_305_843_007_731_562_249),
+            Race::new(3_037_000_500, 2_305_843_009_250_062_499),
+            Race::new(4_000_000_000, 1_000_000_000_000_000_000),
+        ];
+        for race in races {
+            let wins = race.winning_hold_times();
+            assert!(!wins.is_empty(), "{race:?}");
+            assert!(race.is_winning(wins.start), "{race:?}");
+            assert!(!race.is_winning(wins.start - 1), "{race:?}");
+            assert!(race.is_winning(wins.end - 1), "{race:?}");
+            assert!(!race.is_winning(wins.end), "{race:?}");
+        }
+
+        let race = Race::new(i128::MAX - 1, i128::MAX / 3);
+        let wins = race.winning_hold_times();
+        assert!(race.is_winning(wins.start));
+        assert!(!race.is_winning(wins.start - 1));
+        assert_eq!(wins.end, race.time_ms - wins.start + 1);
+
+        let race = Race::new(1_000_000_000_000_000_000_i128, 123_456_789_012_345_678_901);
+        let wins = race.winning_hold_times();
+        assert!(race.is_winning(wins.start));
+        assert!(!race.is_winning(wins.start - 1));
+    }
+}
@@ -0,0 +1,68 @@
+use std::{hint::black_box, time::Instant};
+
+use day_7::{Card, Cards, HandType, Rules};
+use itertools::Itertools;
+
+const HANDS: usize = 1_000_000;
+const SEED: u64 = 0x2023_0007;
+
+/// Xorshift generator, so every run classifies the same hands
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn hand(&mut self) -> Cards {
+        [(); 5].map(|_| Card::ALL[(self.next() % 13) as usize])
+    }
+}
+
+/// The previous classification, which counts the cards and the counts with hash maps
+fn classify_with_hash_maps(cards: &Cards) -> HandType {
+    let card_counts = cards.iter().counts();
+    let counts_counts = card_counts.values().counts();
+    let n_counts = |n: usize| *counts_counts.get(&n).unwrap_or(&0);
+    if n_counts(5) == 1 {
+        HandType::FiveOfAKind
+    } else if n_counts(4) == 1 {
+        HandType::FourOfAKind
+    } else if n_counts(3) == 1 && n_counts(2) == 1 {
+        HandType::FullHouse
+    } else if n_counts(3) == 1 {
+        HandType::ThreeOfAKind
+    } else if n_counts(2) == 2 {
+        HandType::TwoPair
+    } else if n_counts(2) == 1 {
+        HandType::OnePair
+    } else {
+        HandType::HighCard
+    }
+}
+
+fn measure(name: &str, hands: &[Cards], classify: impl Fn(&Cards) -> HandType) -> f64 {
+    let start = Instant::now();
+    let types: Vec<_> = hands.iter().map(|hand| classify(black_box(hand))).collect();
+    let seconds = start.elapsed().as_secs_f64();
+    black_box(types);
+    println!("{name:<12} {:>8.2} ms", seconds * 1000.0);
+    seconds
+}
+
+fn main() {
+    let mut random = Random(SEED);
+    let hands: Vec<_> = (0..HANDS).map(|_| random.hand()).collect();
+    let rules = Rules::standard();
+    for hand in &hands {
+        assert_eq!(classify_with_hash_maps(hand), rules.hand_type(hand));
+    }
+
+    println!("Classifying {HANDS} random hands");
+    let hash_maps = measure("hash maps", &hands, classify_with_hash_maps);
+    let signature = measure("signature", &hands, |hand| rules.hand_type(hand));
+    println!("Speedup      {:>8.1}x", hash_maps / signature);
+}
@@ -0,0 +1,445 @@
+use std::cmp::Reverse;
+
+use anyhow::{bail, Context, Result};
+use nom::{
+    character::complete::{multispace1, one_of, space1},
+    combinator::{all_consuming, map, map_opt},
+    multi::{count, separated_list1},
+    sequence::separated_pair,
+};
+use serde::Serialize;
+use utils::parsing::{self, parse_with_nom};
+
+mod report;
+
+pub use report::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Card {
+    N2,
+    N3,
+    N4,
+    N5,
+    N6,
+    N7,
+    N8,
+    N9,
+    T,
+    J,
+    Q,
+    K,
+    A,
+}
+
+impl Card {
+    pub const ALL: [Card; 13] = [
+        Card::N2,
+        Card::N3,
+        Card::N4,
+        Card::N5,
+        Card::N6,
+        Card::N7,
+        Card::N8,
+        Card::N9,
+        Card::T,
+        Card::J,
+        Card::Q,
+        Card::K,
+        Card::A,
+    ];
+
+    pub fn from_char(c: char) -> Option<Self> {
+        Self::ALL.into_iter().find(|card| card.to_char() == c)
+    }
+
+    pub fn to_char(self) -> char {
+        "23456789TJQKA".as_bytes()[self.index()] as char
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+pub type Cards = [Card; 5];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hand {
+    pub cards: Cards,
+    pub bid: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum HandType {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
+
+impl HandType {
+    /// Classify a hand without wildcards
+    pub fn from_cards(cards: &Cards) -> Self {
+        Self::from_counts(&count_cards(cards.iter()), 0)
+    }
+
+    /// Classify a hand by how often each card occurs, with the wildcards joining the largest group
+    pub fn from_counts(counts: &[u8; 13], wildcards: u8) -> Self {
+        match count_signature(counts) {
+            (largest, _) if largest + wildcards >= 5 => Self::FiveOfAKind,
+            (largest, _) if largest + wildcards == 4 => Self::FourOfAKind,
+            (largest, 2) if largest + wildcards == 3 => Self::FullHouse,
+            (largest, _) if largest + wildcards == 3 => Self::ThreeOfAKind,
+            (2, 2) => Self::TwoPair,
+            (largest, _) if largest + wildcards == 2 => Self::OnePair,
+            _ => Self::HighCard,
+        }
+    }
+}
+
+/// Count how often each card occurs, indexed by [`Card::index`]
+pub fn count_cards<'a>(cards: impl Iterator<Item = &'a Card>) -> [u8; 13] {
+    let mut counts = [0; 13];
+    for card in cards {
+        counts[card.index()] += 1;
+    }
+    counts
+}
+
+/// The two largest counts, which are enough to tell the hand types apart
+fn count_signature(counts: &[u8; 13]) -> (u8, u8) {
+    counts.iter().fold((0, 0), |(largest, second), &count| {
+        if count > largest {
+            (count, largest)
+        } else {
+            (largest, second.max(count))
+        }
+    })
+}
+
+/// How hands with the same type are ordered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Compare the cards in the order they were dealt
+    DealtOrder,
+    /// Compare the cards of the largest groups first, like in poker
+    GroupsFirst,
+}
+
+/// How wildcards are substituted to find the best hand type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WildcardMode {
+    /// Turn all wildcards into the most frequent other card
+    MostFrequent,
+    /// Try every possible substitution, which is slow for many wildcards
+    Exhaustive,
+}
+
+/// Sorting a hand by this key ranks it according to the rules
+pub type HandKey = (HandType, [u8; 5]);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    /// Rank of every card, indexed by [`Card::index`]
+    ranks: [u8; 13],
+    /// Cards which can stand in for any other card, indexed by [`Card::index`]
+    wildcards: [bool; 13],
+    wildcard_mode: WildcardMode,
+    tie_break: TieBreak,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl Rules {
+    /// Camel Cards without wildcards
+    pub fn standard() -> Self {
+        Self {
+            ranks: Card::ALL.map(|card| card.index() as u8),
+            wildcards: [false; 13],
+            wildcard_mode: WildcardMode::MostFrequent,
+            tie_break: TieBreak::DealtOrder,
+        }
+    }
+
+    /// Camel Cards with jokers, which are wild but the weakest card on their own
+    pub fn jokers() -> Self {
+        Self::standard()
+            .with_order("J23456789TQKA")
+            .expect("Valid card order")
+            .with_wildcards(&[Card::J])
+    }
+
+    /// Set the ranking of the cards from the weakest to the strongest, e.g. `23456789TJQKA`
+    pub fn with_order(mut self, order: &str) -> Result<Self> {
+        let mut ranks = [None; 13];
+        for (rank, c) in order.chars().enumerate() {
+            let card = Card::from_char(c).with_context(|| format!("Unknown card '{c}'"))?;
+            if ranks[card.index()].replace(rank as u8).is_some() {
+                bail!("Card '{c}' is ranked twice");
+            }
+        }
+        for card in Card::ALL {
+            self.ranks[card.index()] = ranks[card.index()]
+                .with_context(|| format!("Card '{}' is not ranked", card.to_char()))?;
+        }
+        Ok(self)
+    }
+
+    pub fn with_wildcards(mut self, wildcards: &[Card]) -> Self {
+        self.wildcards = [false; 13];
+        for card in wildcards {
+            self.wildcards[card.index()] = true;
+        }
+        self
+    }
+
+    pub fn with_wildcard_mode(mut self, wildcard_mode: WildcardMode) -> Self {
+        self.wildcard_mode = wildcard_mode;
+        self
+    }
+
+    pub fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    pub fn rank(&self, card: Card) -> u8 {
+        self.ranks[card.index()]
+    }
+
+    pub fn is_wild(&self, card: Card) -> bool {
+        self.wildcards[card.index()]
+    }
+
+    pub fn hand_type(&self, cards: &Cards) -> HandType {
+        match self.wildcard_mode {
+            WildcardMode::MostFrequent => {
+                let counts = count_cards(cards.iter().filter(|card| !self.is_wild(**card)));
+                let wildcards = cards.iter().filter(|card| self.is_wild(**card)).count();
+                HandType::from_counts(&counts, wildcards as u8)
+            }
+            WildcardMode::Exhaustive => HandType::from_cards(&self.substitute_exhaustive(cards)),
+        }
+    }
+
+    /// Replace the wildcards with the cards forming the best hand type
+    pub fn substitute_wildcards(&self, cards: &Cards) -> Cards {
+        match self.wildcard_mode {
+            WildcardMode::MostFrequent => self.substitute_most_frequent(cards),
+            WildcardMode::Exhaustive => self.substitute_exhaustive(cards),
+        }
+    }
+
+    fn substitute_most_frequent(&self, cards: &Cards) -> Cards {
+        let counts = count_cards(cards.iter().filter(|card| !self.is_wild(**card)));
+        // A hand of only wildcards becomes five of the strongest card
+        let best = Card::ALL
+            .into_iter()
+            .max_by_key(|card| (counts[card.index()], self.rank(*card)))
+            .expect("There are cards");
+        cards.map(|card| if self.is_wild(card) { best } else { card })
+    }
+
+    fn substitute_exhaustive(&self, cards: &Cards) -> Cards {
+        let mut best = *cards;
+        let mut candidate = *cards;
+        self.try_substitutions(cards, 0, &mut candidate, &mut best);
+        best
+    }
+
+    fn try_substitutions(&self, cards: &Cards, i: usize, candidate: &mut Cards, best: &mut Cards) {
+        if i == cards.len() {
+            if HandType::from_cards(candidate) > HandType::from_cards(best) {
+                *best = *candidate;
+            }
+            return;
+        }
+        if !self.is_wild(cards[i]) {
+            return self.try_substitutions(cards, i + 1, candidate, best);
+        }
+        for card in Card::ALL {
+            candidate[i] = card;
+            self.try_substitutions(cards, i + 1, candidate, best);
+        }
+    }
+
+    pub fn key(&self, cards: &Cards) -> HandKey {
+        let mut ranks = cards.map(|card| self.rank(card));
+        if self.tie_break == TieBreak::GroupsFirst {
+            let group_size = |rank: u8| ranks.iter().filter(|r| **r == rank).count();
+            let mut sorted = ranks;
+            sorted.sort_by_key(|rank| Reverse((group_size(*rank), *rank)));
+            ranks = sorted;
+        }
+        (self.hand_type(cards), ranks)
+    }
+
+    /// Sort the hands from the weakest to the strongest, so the rank of a hand is its index + 1
+    pub fn rank_hands<'a>(&self, hands: &'a [Hand]) -> Vec<&'a Hand> {
+        let mut hands: Vec<_> = hands.iter().collect();
+        hands.sort_by_cached_key(|hand| self.key(&hand.cards));
+        hands
+    }
+
+    /// Sum up the bids multiplied by the rank of their hand
+    pub fn total_winnings(&self, hands: &[Hand]) -> usize {
+        self.rank_hands(hands)
+            .iter()
+            .enumerate()
+            .map(|(i, hand)| hand.bid * (i + 1))
+            .sum()
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<Hand>> {
+    let parse_card = map_opt(one_of("23456789TJQKA"), Card::from_char);
+    let parse_cards = map(count(parse_card, 5), |cards| {
+        [cards[0], cards[1], cards[2], cards[3], cards[4]]
+    });
+    let parse_hand = map(
+        separated_pair(parse_cards, space1, parsing::number),
+        |(cards, bid)| Hand { cards, bid },
+    );
+    parse_with_nom(
+        input.trim(),
+        all_consuming(separated_list1(multispace1, parse_hand)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read_to_string;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn cards(s: &str) -> Cards {
+        let cards: Vec<_> = s.chars().map(|c| Card::from_char(c).unwrap()).collect();
+        cards.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_example() {
+        let input = read_to_string("./example.txt").unwrap();
+        let hands = parse(&input).unwrap();
+
+        assert_eq!(Rules::standard().total_winnings(&hands), 6440);
+        assert_eq!(Rules::jokers().total_winnings(&hands), 5905);
+    }
+
+    #[test]
+    fn test_hand_type() {
+        let standard = Rules::standard();
+        let jokers = Rules::jokers();
+        assert_eq!(standard.hand_type(&cards("KTJJT")), HandType::TwoPair);
+        assert_eq!(jokers.hand_type(&cards("KTJJT")), HandType::FourOfAKind);
+        assert_eq!(jokers.hand_type(&cards("JJJJJ")), HandType::FiveOfAKind);
+        assert_eq!(jokers.hand_type(&cards("2345J")), HandType::OnePair);
+        assert_eq!(standard.hand_type(&cards("23332")), HandType::FullHouse);
+        assert_eq!(jokers.hand_type(&cards("2233J")), HandType::FullHouse);
+    }
+
+    #[test]
+    fn test_from_counts() {
+        let counts = count_cards(cards("KTJJT").iter());
+        assert_eq!(count_signature(&counts), (2, 2));
+        assert_eq!(HandType::from_counts(&counts, 0), HandType::TwoPair);
+        assert_eq!(HandType::from_counts(&counts, 1), HandType::FullHouse);
+        assert_eq!(HandType::from_counts(&counts, 2), HandType::FourOfAKind);
+        assert_eq!(HandType::from_counts(&[0; 13], 5), HandType::FiveOfAKind);
+        assert_eq!(
+            HandType::from_counts(
+                &count_cards([Card::N2, Card::N3, Card::N4, Card::N5].iter()),
+                1
+            ),
+            HandType::OnePair
+        );
+    }
+
+    #[test]
+    fn test_multiple_wildcards() {
+        let rules = Rules::standard().with_wildcards(&[Card::J, Card::N2]);
+        assert_eq!(rules.hand_type(&cards("J2345")), HandType::ThreeOfAKind);
+        assert_eq!(rules.hand_type(&cards("J2K2K")), HandType::FiveOfAKind);
+        assert_eq!(rules.rank(Card::J), Rules::standard().rank(Card::J));
+    }
+
+    #[test]
+    fn test_substitute_wildcards() {
+        let rules = Rules::jokers();
+        assert_eq!(rules.substitute_wildcards(&cards("KTJJT")), cards("KTTTT"));
+        assert_eq!(rules.substitute_wildcards(&cards("JJJJJ")), cards("AAAAA"));
+        assert_eq!(rules.substitute_wildcards(&cards("Q2J2Q")), cards("Q2Q2Q"));
+        let exhaustive = rules.with_wildcard_mode(WildcardMode::Exhaustive);
+        assert_eq!(
+            HandType::from_cards(&exhaustive.substitute_wildcards(&cards("KTJJT"))),
+            HandType::FourOfAKind
+        );
+    }
+
+    #[test]
+    fn test_most_frequent_substitution_is_optimal() {
+        let most_frequent = Rules::jokers();
+        let exhaustive = Rules::jokers().with_wildcard_mode(WildcardMode::Exhaustive);
+        let mut hand = [Card::N2; 5];
+        for i in 0..13_usize.pow(5) {
+            let mut n = i;
+            for card in hand.iter_mut() {
+                *card = Card::ALL[n % 13];
+                n /= 13;
+            }
+            let best = exhaustive.hand_type(&hand);
+            assert_eq!(most_frequent.hand_type(&hand), best, "{hand:?}");
+            assert_eq!(
+                HandType::from_cards(&most_frequent.substitute_wildcards(&hand)),
+                best,
+                "{hand:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_tie_break() {
+        let dealt = Rules::standard();
+        let poker = Rules::standard().with_tie_break(TieBreak::GroupsFirst);
+        let (a, b) = (cards("A2233"), cards("K44QQ"));
+        assert!(dealt.key(&a) > dealt.key(&b));
+        assert!(poker.key(&a) < poker.key(&b));
+        assert_eq!(
+            poker.key(&cards("3A3A2")),
+            (HandType::TwoPair, [12, 12, 1, 1, 0])
+        );
+    }
+
+    #[test]
+    fn test_with_order() {
+        let rules = Rules::jokers();
+        assert!(rules.rank(Card::J) < rules.rank(Card::N2));
+        assert_eq!(
+            Rules::standard()
+                .with_order("23456789TJQK")
+                .unwrap_err()
+                .to_string(),
+            "Card 'A' is not ranked"
+        );
+        assert_eq!(
+            Rules::standard()
+                .with_order("22345")
+                .unwrap_err()
+                .to_string(),
+            "Card '2' is ranked twice"
+        );
+        assert_eq!(
+            Rules::standard().with_order("X").unwrap_err().to_string(),
+            "Unknown card 'X'"
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::{Cards, Hand, HandType, Rules};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Table,
+    ColoredTable,
+    Json,
+}
+
+impl FromStr for ReportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "table" => Ok(Self::Table),
+            "color" => Ok(Self::ColoredTable),
+            "json" => Ok(Self::Json),
+            _ => bail!("Unknown report format '{s}', expected 'table', 'color' or 'json'"),
+        }
+    }
+}
+
+/// Explanation of how every hand was ranked
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Report {
+    /// Ordered from the weakest to the strongest hand
+    pub hands: Vec<RankedHand>,
+    pub total_winnings: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RankedHand {
+    pub rank: usize,
+    pub cards: String,
+    /// The cards after substituting the wildcards
+    pub played_as: String,
+    pub hand_type: HandType,
+    pub bid: usize,
+    pub winnings: usize,
+    /// Whether a neighbouring hand has the same type, so the order was decided by the cards
+    pub tie_broken_by_cards: bool,
+}
+
+fn cards_to_string(cards: &Cards) -> String {
+    cards.iter().map(|card| card.to_char()).collect()
+}
+
+impl Rules {
+    pub fn report(&self, hands: &[Hand]) -> Report {
+        let ranked = self.rank_hands(hands);
+        let hand_types: Vec<_> = ranked.iter().map(|h| self.hand_type(&h.cards)).collect();
+        let hands: Vec<_> = ranked
+            .iter()
+            .enumerate()
+            .map(|(i, hand)| {
+                let hand_type = hand_types[i];
+                let same_type = |j: Option<usize>| {
+                    j.and_then(|j| hand_types.get(j))
+                        .is_some_and(|t| *t == hand_type)
+                };
+                RankedHand {
+                    rank: i + 1,
+                    cards: cards_to_string(&hand.cards),
+                    played_as: cards_to_string(&self.substitute_wildcards(&hand.cards)),
+                    hand_type,
+                    bid: hand.bid,
+                    winnings: hand.bid * (i + 1),
+                    tie_broken_by_cards: same_type(i.checked_sub(1)) || same_type(Some(i + 1)),
+                }
+            })
+            .collect();
+        Report {
+            total_winnings: hands.iter().map(|hand| hand.winnings).sum(),
+            hands,
+        }
+    }
+}
+
+impl Report {
+    pub fn render(&self, format: ReportFormat) -> Result<String> {
+        match format {
+            ReportFormat::Table => Ok(self.table(false)),
+            ReportFormat::ColoredTable => Ok(self.table(true)),
+            ReportFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+        }
+    }
+
+    fn table(&self, colored: bool) -> String {
+        let header = format!(
+            "{:>6}  {:<5}  {:<9}  {:<12}  {:>6}  {:>10}",
+            "Rank", "Cards", "Played as", "Type", "Bid", "Winnings"
+        );
+        let mut lines = vec![if colored {
+            header.bold().to_string()
+        } else {
+            header
+        }];
+        for hand in &self.hands {
+            let hand_type = format!("{:?}", hand.hand_type);
+            let line = format!(
+                "{:>6}  {:<5}  {:<9}  {:<12}  {:>6}  {:>10}",
+                hand.rank, hand.cards, hand.played_as, hand_type, hand.bid, hand.winnings
+            );
+            lines.push(match (hand.tie_broken_by_cards, colored) {
+                (true, true) => line.yellow().to_string(),
+                (true, false) => format!("{line}  *"),
+                (false, _) => line,
+            });
+        }
+        lines.push(format!("Total winnings: {}", self.total_winnings));
+        if self.hands.iter().any(|hand| hand.tie_broken_by_cards) {
+            let legend = "Hands of the same type are ordered by their cards";
+            lines.push(if colored {
+                legend.yellow().to_string()
+            } else {
+                format!("* {legend}")
+            });
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read_to_string;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::parse;
+
+    use super::*;
+
+    fn example_report() -> Report {
+        let input = read_to_string("./example.txt").unwrap();
+        Rules::jokers().report(&parse(&input).unwrap())
+    }
+
+    #[test]
+    fn test_report() {
+        let report = example_report();
+        assert_eq!(report.total_winnings, 5905);
+        assert_eq!(
+            report.hands[4],
+            RankedHand {
+                rank: 5,
+                cards: "KTJJT".into(),
+                played_as: "KTTTT".into(),
+                hand_type: HandType::FourOfAKind,
+                bid: 220,
+                winnings: 1100,
+                tie_broken_by_cards: true,
+            }
+        );
+        let ties: Vec<_> = report
+            .hands
+            .iter()
+            .map(|hand| hand.tie_broken_by_cards)
+            .collect();
+        assert_eq!(ties, [false, false, true, true, true]);
+    }
+
+    #[test]
+    fn test_table() {
+        let table = example_report().render(ReportFormat::Table).unwrap();
+        let expected = [
+            "  Rank  Cards  Played as  Type             Bid    Winnings",
+            "     1  32T3K  32T3K      OnePair          765         765",
+            "     2  KK677  KK677      TwoPair           28          56",
+            "     3  T55J5  T5555      FourOfAKind      684        2052  *",
+            "     4  QQQJA  QQQQA      FourOfAKind      483        1932  *",
+            "     5  KTJJT  KTTTT      FourOfAKind      220        1100  *",
+            "Total winnings: 5905",
+            "* Hands of the same type are ordered by their cards",
+        ];
+        assert_eq!(table, expected.join("\n"));
+    }
+
+    #[test]
+    fn test_json() {
+        let json = example_report().render(ReportFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["total_winnings"], 5905);
+        assert_eq!(value["hands"][2]["played_as"], "T5555");
+        assert_eq!(value["hands"][2]["hand_type"], "FourOfAKind");
+        assert_eq!(value["hands"][2]["tie_broken_by_cards"], true);
+    }
+
+    #[test]
+    fn test_report_format() {
+        assert_eq!(
+            "color".parse::<ReportFormat>().unwrap(),
+            ReportFormat::ColoredTable
+        );
+        assert!("csv".parse::<ReportFormat>().is_err());
+    }
+}
@@ -0,0 +1,247 @@
+use std::{error, fmt};
+
+use crate::{Node, Problem};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkError {
+    /// Following the directions from `start` never leads to a target
+    UnreachableTarget { start: String },
+    /// The paths are never on a target at the same time
+    NeverSynchronized,
+    /// The step count doesn't fit into an `usize`
+    Overflow,
+    /// The targets of the paths can be combined in more ways than [`crate::MAX_CONGRUENCES`]
+    TooManyCongruences,
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnreachableTarget { start } => {
+                write!(f, "No target can be reached from '{start}'")
+            }
+            Self::NeverSynchronized => {
+                write!(f, "The paths are never on a target at the same step")
+            }
+            Self::Overflow => write!(f, "The number of steps is too large"),
+            Self::TooManyCongruences => {
+                write!(f, "The paths have too many combinations of targets")
+            }
+        }
+    }
+}
+
+impl error::Error for NetworkError {}
+
+/// The path from a start node, which always ends in a cycle because the network and the
+/// directions are finite. Steps are counted from the start, which is step 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathStructure {
+    /// Steps before the path enters its cycle
+    pub transient_len: usize,
+    pub cycle_len: usize,
+    /// Steps in the transient at which the path is on a target
+    pub transient_targets: Vec<usize>,
+    /// Positions of the targets within the cycle, counted from its first step
+    pub cycle_targets: Vec<usize>,
+}
+
+impl PathStructure {
+    pub fn reaches_target(&self) -> bool {
+        !self.transient_targets.is_empty() || !self.cycle_targets.is_empty()
+    }
+
+    /// The step at which the path is on a target for the first time
+    pub fn first_target(&self) -> Option<usize> {
+        self.transient_targets.first().copied().or_else(|| {
+            self.cycle_targets
+                .first()
+                .map(|position| self.transient_len + position)
+        })
+    }
+}
+
+impl fmt::Display for PathStructure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} steps before a cycle of {} steps",
+            self.transient_len, self.cycle_len
+        )?;
+        if !self.reaches_target() {
+            return write!(f, ", never on a target");
+        }
+        if !self.transient_targets.is_empty() {
+            write!(f, ", targets at steps {:?}", self.transient_targets)?;
+        }
+        if !self.cycle_targets.is_empty() {
+            write!(f, ", targets at cycle positions {:?}", self.cycle_targets)?;
+        }
+        Ok(())
+    }
+}
+
+impl Problem<'_> {
+    /// Walk from `start` until a node is reached at the same position in the directions again
+    pub fn path_structure(&self, start: Node, is_target: impl Fn(Node) -> bool) -> PathStructure {
+        let period = self.directions.len();
+        let mut first_visits = vec![None; self.junctions.len() * period];
+        let mut targets = Vec::new();
+        let mut node = start;
+        let mut step = 0;
+        loop {
+            let state = node.0.index() * period + step % period;
+            if let Some(cycle_start) = first_visits[state] {
+                let split = targets.partition_point(|target| *target < cycle_start);
+                return PathStructure {
+                    transient_len: cycle_start,
+                    cycle_len: step - cycle_start,
+                    transient_targets: targets[..split].to_vec(),
+                    cycle_targets: targets[split..]
+                        .iter()
+                        .map(|target| target - cycle_start)
+                        .collect(),
+                };
+            }
+            first_visits[state] = Some(step);
+            if is_target(node) {
+                targets.push(step);
+            }
+            node = *self.junction(node).get(&self.directions[step % period]);
+            step += 1;
+        }
+    }
+
+    /// The number of steps from `start` to the first target
+    pub fn steps_to_target(
+        &self,
+        start: Node,
+        is_target: impl Fn(Node) -> bool,
+    ) -> Result<usize, NetworkError> {
+        self.path_structure(start, is_target)
+            .first_target()
+            .ok_or_else(|| NetworkError::UnreachableTarget {
+                start: self.name(start).to_string(),
+            })
+    }
+
+    /// Nodes which lead back to themselves in both directions
+    pub fn dead_ends(&self) -> Vec<Node> {
+        self.junctions
+            .iter()
+            .filter(|j| j.left == j.node && j.right == j.node)
+            .map(|j| j.node)
+            .collect()
+    }
+
+    pub fn report(&self, starts: &[Node], is_target: impl Fn(Node) -> bool) -> NetworkReport {
+        NetworkReport {
+            dead_ends: self
+                .dead_ends()
+                .into_iter()
+                .map(|node| self.name(node).to_string())
+                .collect(),
+            paths: starts
+                .iter()
+                .map(|start| {
+                    (
+                        self.name(*start).to_string(),
+                        self.path_structure(*start, &is_target),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkReport {
+    pub dead_ends: Vec<String>,
+    /// The path from every start node
+    pub paths: Vec<(String, PathStructure)>,
+}
+
+impl fmt::Display for NetworkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Dead ends: ")?;
+        if self.dead_ends.is_empty() {
+            write!(f, "none")?;
+        } else {
+            write!(f, "{}", self.dead_ends.join(", "))?;
+        }
+        for (start, path) in &self.paths {
+            write!(f, "\n{start}: {path}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read_to_string;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::parse;
+
+    use super::*;
+
+    #[test]
+    fn test_path_structure() {
+        let input = read_to_string("./example.txt").unwrap();
+        let problem = parse(&input).unwrap();
+        let target = problem.node("ZZZ").unwrap();
+
+        let path = problem.path_structure(problem.node("AAA").unwrap(), |n| n == target);
+        assert_eq!(
+            path,
+            PathStructure {
+                transient_len: 2,
+                cycle_len: 2,
+                transient_targets: vec![],
+                cycle_targets: vec![0, 1],
+            }
+        );
+        assert_eq!(path.first_target(), Some(2));
+    }
+
+    #[test]
+    fn test_unreachable_target() {
+        let input = read_to_string("./example.txt").unwrap();
+        let problem = parse(&input).unwrap();
+        let target = problem.node("ZZZ").unwrap();
+
+        let result = problem.steps_to_target(problem.node("BBB").unwrap(), |n| n == target);
+        assert_eq!(
+            result,
+            Err(NetworkError::UnreachableTarget {
+                start: "BBB".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_undefined_node() {
+        let input = "LR\n\nAAA = (BBB, CCC)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)";
+        let err = parse(input).unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "Every referenced node needs to be defined exactly once: Missing entry for 'CCC'"
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let input = read_to_string("./example3.txt").unwrap();
+        let problem = parse(&input).unwrap();
+        let targets = problem.nodes_ending_with('Z');
+
+        let report = problem.report(&problem.nodes_ending_with('A'), |n| targets.contains(&n));
+        assert_eq!(
+            report.to_string(),
+            "Dead ends: XXX\n\
+             11A: 1 steps before a cycle of 2 steps, targets at cycle positions [1]\n\
+             22A: 1 steps before a cycle of 6 steps, targets at cycle positions [2, 5]"
+        );
+    }
+}
@@ -1,15 +1,6 @@
-use std::{collections::HashMap, fmt::Debug};
-
 use anyhow::{Context, Result};
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{anychar, multispace1},
-    combinator::{all_consuming, map},
-    multi::{count, many1, separated_list1},
-    sequence::{delimited, preceded, tuple},
-};
-use utils::{lcm, parsing::parse_with_nom, read_input_file_as_string};
+use day_8::{parse, synchronize, NetworkError};
+use utils::{is_debugging, read_input_file_as_string};
 
 fn main() -> Result<()> {
     let input = read_input_file_as_string().context("Cannot read input")?;
@@ -19,97 +10,25 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn solve_problem(input: &str) -> Result<i64> {
+fn solve_problem(input: &str) -> Result<usize> {
     let problem = parse(input)?;
-    let starting_nodes: Vec<Node> = problem
-        .junctions
-        .keys()
-        .filter(|k| k.ends_with('A'))
-        .copied()
-        .collect();
-    let counts = starting_nodes.into_iter().map(|starting_node| {
-        let mut node = starting_node;
-        let mut count = 0;
-        for direction in problem.directions.iter().cycle() {
-            if node.ends_with('Z') {
-                break;
-            }
-            node = *problem.junctions[&node].get(direction);
-            count += 1;
-        }
-        count
-    });
-    let result = counts.fold(1, lcm);
-    Ok(result)
-}
-
-#[derive(Debug, Clone)]
-struct Problem {
-    pub directions: Vec<Direction>,
-    pub junctions: HashMap<Node, Junction>,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct Node([char; 3]);
-
-impl Node {
-    fn ends_with(&self, c: char) -> bool {
-        self.0.last() == Some(&c)
+    let starts = problem.nodes_ending_with('A');
+    let targets = problem.nodes_ending_with('Z');
+    let is_target = |node| targets.contains(&node);
+    if is_debugging() {
+        println!("{}", problem.report(&starts, is_target));
     }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct Junction {
-    pub node: Node,
-    pub left: Node,
-    pub right: Node,
-}
-
-impl Junction {
-    fn get(&self, direction: &Direction) -> &Node {
-        match direction {
-            Direction::Left => &self.left,
-            Direction::Right => &self.right,
+    let mut paths = Vec::new();
+    for start in starts {
+        let path = problem.path_structure(start, is_target);
+        if !path.reaches_target() {
+            Err(NetworkError::UnreachableTarget {
+                start: problem.name(start).to_string(),
+            })?;
         }
+        paths.push(path);
     }
-}
-
-#[derive(Debug, Clone)]
-enum Direction {
-    Left,
-    Right,
-}
-
-fn parse(input: &str) -> Result<Problem> {
-    let parse_direction = || {
-        alt((
-            map(tag("L"), |_| Direction::Left),
-            map(tag("R"), |_| Direction::Right),
-        ))
-    };
-    let parse_node = || map(count(anychar, 3), |s| Node(s.try_into().unwrap()));
-    let parse_junction = || {
-        map(
-            tuple((
-                parse_node(),
-                preceded(tag(" = ("), parse_node()),
-                delimited(tag(", "), parse_node(), tag(")")),
-            )),
-            |(node, left, right)| Junction { node, left, right },
-        )
-    };
-    let parse_problem = map(
-        tuple((
-            many1(parse_direction()),
-            preceded(multispace1, separated_list1(multispace1, parse_junction())),
-        )),
-        |(directions, junctions)| Problem {
-            directions,
-            junctions: junctions.into_iter().map(|j| (j.node, j)).collect(),
-        },
-    );
-    let problem = parse_with_nom(input.trim(), all_consuming(parse_problem))?;
-    Ok(problem)
+    Ok(synchronize(&paths)?)
 }
 
 #[cfg(test)]
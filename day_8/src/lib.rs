@@ -0,0 +1,116 @@
+use std::cell::RefCell;
+
+use anyhow::{Context, Result};
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take},
+    character::complete::multispace1,
+    combinator::{all_consuming, map},
+    multi::{many1, separated_list1},
+    sequence::{delimited, preceded, tuple},
+};
+use utils::{
+    intern::{Id, Interner},
+    parsing::parse_with_nom,
+};
+
+mod analysis;
+mod synchronization;
+
+pub use analysis::*;
+pub use synchronization::*;
+
+#[derive(Debug, Clone)]
+pub struct Problem<'a> {
+    pub directions: Vec<Direction>,
+    /// Indexed by [`Node`]
+    pub junctions: Vec<Junction>,
+    pub nodes: Interner<'a>,
+}
+
+impl<'a> Problem<'a> {
+    pub fn junction(&self, node: Node) -> &Junction {
+        &self.junctions[node.0.index()]
+    }
+
+    pub fn node(&self, name: &str) -> Result<Node> {
+        let id = self
+            .nodes
+            .get(name)
+            .with_context(|| format!("Node '{name}' not found"))?;
+        Ok(Node(id))
+    }
+
+    pub fn name(&self, node: Node) -> &'a str {
+        self.nodes.resolve(node.0)
+    }
+
+    /// All nodes whose name ends with `suffix`, ordered by their first appearance
+    pub fn nodes_ending_with(&self, suffix: char) -> Vec<Node> {
+        self.nodes
+            .iter()
+            .filter(|(_, name)| name.ends_with(suffix))
+            .map(|(id, _)| Node(id))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Node(pub Id);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Junction {
+    pub node: Node,
+    pub left: Node,
+    pub right: Node,
+}
+
+impl Junction {
+    pub fn get(&self, direction: &Direction) -> &Node {
+        match direction {
+            Direction::Left => &self.left,
+            Direction::Right => &self.right,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+pub fn parse(input: &str) -> Result<Problem<'_>> {
+    let nodes = RefCell::new(Interner::new());
+    let parse_direction = || {
+        alt((
+            map(tag("L"), |_| Direction::Left),
+            map(tag("R"), |_| Direction::Right),
+        ))
+    };
+    let parse_node = || map(take(3usize), |s| Node(nodes.borrow_mut().intern(s)));
+    let parse_junction = || {
+        map(
+            tuple((
+                parse_node(),
+                preceded(tag(" = ("), parse_node()),
+                delimited(tag(", "), parse_node(), tag(")")),
+            )),
+            |(node, left, right)| Junction { node, left, right },
+        )
+    };
+    let parse_problem = tuple((
+        many1(parse_direction()),
+        preceded(multispace1, separated_list1(multispace1, parse_junction())),
+    ));
+    let (directions, junctions) = parse_with_nom(input.trim(), all_consuming(parse_problem))?;
+    let nodes = nodes.into_inner();
+    let junctions = nodes
+        .table(junctions.into_iter().map(|j| (j.node.0, j)))
+        .context("Every referenced node needs to be defined exactly once")?;
+    Ok(Problem {
+        directions,
+        junctions,
+        nodes,
+    })
+}
@@ -0,0 +1,226 @@
+use std::collections::BTreeSet;
+
+use utils::gcd;
+
+use crate::{NetworkError, PathStructure};
+
+impl PathStructure {
+    /// Check if the path is on a target after `step` steps
+    pub fn is_target_at(&self, step: usize) -> bool {
+        if step < self.transient_len {
+            self.transient_targets.binary_search(&step).is_ok()
+        } else {
+            let position = (step - self.transient_len) % self.cycle_len;
+            self.cycle_targets.binary_search(&position).is_ok()
+        }
+    }
+
+    /// The path is on a target exactly at the multiples of the cycle length
+    fn hits_at_cycle_multiples(&self) -> bool {
+        self.transient_targets.is_empty()
+            && self.cycle_targets.len() == 1
+            && self.transient_len + self.cycle_targets[0] == self.cycle_len
+    }
+}
+
+/// All steps `s` with `s % modulus == residue`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Congruence {
+    residue: i128,
+    modulus: i128,
+}
+
+impl Congruence {
+    /// The steps which satisfy both congruences, by the generalized Chinese remainder theorem
+    fn combine(&self, other: &Self) -> Result<Option<Self>, NetworkError> {
+        let g = gcd(self.modulus, other.modulus);
+        let difference = other.residue - self.residue;
+        if difference % g != 0 {
+            return Ok(None);
+        }
+        let modulus = (self.modulus / g)
+            .checked_mul(other.modulus)
+            .ok_or(NetworkError::Overflow)?;
+        let other_modulus = other.modulus / g;
+        let factor = (difference / g).rem_euclid(other_modulus)
+            * modular_inverse(self.modulus / g, other_modulus)
+            % other_modulus;
+        let residue = (self.residue + self.modulus * factor).rem_euclid(modulus);
+        Ok(Some(Self { residue, modulus }))
+    }
+
+    /// The smallest step which is at least `min`
+    fn first_from(&self, min: i128) -> i128 {
+        if self.residue >= min {
+            self.residue
+        } else {
+            let cycles = (min - self.residue + self.modulus - 1) / self.modulus;
+            self.residue + cycles * self.modulus
+        }
+    }
+}
+
+/// The inverse of `a` modulo `m`, where `a` and `m` are coprime
+fn modular_inverse(a: i128, m: i128) -> i128 {
+    let (mut old_r, mut r) = (a.rem_euclid(m), m);
+    let (mut old_s, mut s) = (1, 0);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+    }
+    old_s.rem_euclid(m)
+}
+
+/// The most distinct combinations of target positions which are tracked while synchronizing
+pub const MAX_CONGRUENCES: usize = 1 << 16;
+
+/// The first step at which all paths are on a target at the same time, which is step 0
+/// without any paths.
+///
+/// Uses the least common multiple of the cycle lengths if every path is on a target exactly
+/// at the multiples of its cycle length. Otherwise, the steps before the last path enters its
+/// cycle are checked one by one, and the later steps are found by combining the positions of
+/// the targets within the cycles. Every path multiplies the number of combinations by its
+/// number of targets in the cycle, so this fails once there are more than
+/// [`MAX_CONGRUENCES`] distinct ones.
+pub fn synchronize(paths: &[PathStructure]) -> Result<usize, NetworkError> {
+    if paths.is_empty() {
+        return Ok(0);
+    }
+    if paths.iter().all(PathStructure::hits_at_cycle_multiples) {
+        return paths.iter().try_fold(1, |result: usize, path| {
+            (result / gcd(result as i128, path.cycle_len as i128) as usize)
+                .checked_mul(path.cycle_len)
+                .ok_or(NetworkError::Overflow)
+        });
+    }
+    let longest = paths
+        .iter()
+        .max_by_key(|path| path.transient_len)
+        .expect("There is at least one path");
+    if let Some(step) = longest
+        .transient_targets
+        .iter()
+        .copied()
+        .find(|step| paths.iter().all(|path| path.is_target_at(*step)))
+    {
+        return Ok(step);
+    }
+
+    let mut congruences = vec![Congruence {
+        residue: 0,
+        modulus: 1,
+    }];
+    for path in paths {
+        let modulus = path.cycle_len as i128;
+        let mut combined = BTreeSet::new();
+        for position in &path.cycle_targets {
+            let target = Congruence {
+                residue: ((path.transient_len + position) as i128) % modulus,
+                modulus,
+            };
+            for congruence in &congruences {
+                combined.extend(congruence.combine(&target)?);
+                if combined.len() > MAX_CONGRUENCES {
+                    return Err(NetworkError::TooManyCongruences);
+                }
+            }
+        }
+        congruences = combined.into_iter().collect();
+    }
+    let step = congruences
+        .iter()
+        .map(|congruence| congruence.first_from(longest.transient_len as i128))
+        .min()
+        .ok_or(NetworkError::NeverSynchronized)?;
+    usize::try_from(step).map_err(|_| NetworkError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn path(
+        transient_len: usize,
+        cycle_len: usize,
+        transient_targets: &[usize],
+        cycle_targets: &[usize],
+    ) -> PathStructure {
+        PathStructure {
+            transient_len,
+            cycle_len,
+            transient_targets: transient_targets.to_vec(),
+            cycle_targets: cycle_targets.to_vec(),
+        }
+    }
+
+    /// Check every step until `limit`
+    fn synchronize_by_scanning(paths: &[PathStructure], limit: usize) -> Option<usize> {
+        (0..limit).find(|step| paths.iter().all(|path| path.is_target_at(*step)))
+    }
+
+    #[test]
+    fn test_no_paths() {
+        assert_eq!(synchronize(&[]), Ok(0));
+    }
+
+    #[test]
+    fn test_too_many_congruences() {
+        // Coprime cycles with a target at every position but the first
+        let paths = [37, 41, 43, 47].map(|cycle_len: usize| {
+            let positions: Vec<_> = (1..cycle_len).collect();
+            path(0, cycle_len, &[], &positions)
+        });
+        assert_eq!(synchronize(&paths[..3]), Ok(1));
+        assert_eq!(synchronize(&paths), Err(NetworkError::TooManyCongruences));
+    }
+
+    #[test]
+    fn test_lcm_fast_path() {
+        let paths = [path(1, 4, &[], &[3]), path(2, 6, &[], &[4])];
+        assert_eq!(synchronize(&paths), Ok(12));
+    }
+
+    #[test]
+    fn test_cycle_offsets() {
+        // Hits at 3, 8, 13, ... and at 4, 11, 18, ...
+        let paths = [path(1, 5, &[], &[2]), path(2, 7, &[], &[2])];
+        assert_eq!(synchronize(&paths), Ok(18));
+        // Hits at 2, 8, 14, ... and 5, 9, 13, 17, ...
+        let paths = [path(0, 6, &[], &[2]), path(1, 4, &[], &[0])];
+        assert_eq!(synchronize(&paths), Err(NetworkError::NeverSynchronized));
+    }
+
+    #[test]
+    fn test_transient_hits() {
+        let paths = [path(5, 3, &[1, 4], &[0]), path(0, 2, &[], &[0])];
+        assert_eq!(synchronize(&paths), Ok(4));
+        let paths = [path(5, 3, &[1, 3], &[1]), path(0, 2, &[], &[0])];
+        assert_eq!(synchronize(&paths), Ok(6));
+    }
+
+    #[test]
+    fn test_synchronize_matches_scanning() {
+        let shapes = [
+            path(0, 3, &[], &[0, 2]),
+            path(2, 4, &[1], &[1]),
+            path(3, 6, &[0, 2], &[0, 3, 5]),
+            path(1, 5, &[], &[4]),
+            path(4, 2, &[3], &[]),
+            path(0, 7, &[], &[6]),
+        ];
+        for a in &shapes {
+            for b in &shapes {
+                for c in &shapes {
+                    let paths = [a.clone(), b.clone(), c.clone()];
+                    let expected = synchronize_by_scanning(&paths, 1000)
+                        .ok_or(NetworkError::NeverSynchronized);
+                    assert_eq!(synchronize(&paths), expected, "{paths:?}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use std::{
+    fmt,
+    ops::{BitAnd, BitOr, Sub},
+};
+
+/// Set of the values `0..64 * WORDS`, stored as one bit per value
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitSet<const WORDS: usize> {
+    words: [u64; WORDS],
+}
+
+/// Set of the values `0..128`
+pub type BitSet128 = BitSet<2>;
+
+impl<const WORDS: usize> BitSet<WORDS> {
+    /// The number of values which fit into the set
+    pub const CAPACITY: usize = 64 * WORDS;
+
+    pub fn new() -> Self {
+        Self { words: [0; WORDS] }
+    }
+
+    /// Add a value and return whether it was new. Panics if it is not below [`Self::CAPACITY`].
+    pub fn insert(&mut self, value: usize) -> bool {
+        assert!(
+            value < Self::CAPACITY,
+            "{value} doesn't fit into a set of capacity {}",
+            Self::CAPACITY
+        );
+        let (word, bit) = (value / 64, 1 << (value % 64));
+        let is_new = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        is_new
+    }
+
+    /// Remove a value and return whether it was present
+    pub fn remove(&mut self, value: usize) -> bool {
+        let was_present = self.contains(value);
+        if was_present {
+            self.words[value / 64] &= !(1 << (value % 64));
+        }
+        was_present
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        value < Self::CAPACITY && self.words[value / 64] & (1 << (value % 64)) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a | b)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a & b)
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a & !b)
+    }
+
+    /// The number of values in both sets, without building the intersection
+    pub fn intersection_len(&self, other: &Self) -> usize {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    /// Iterate over the values in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(64 * i + bit)
+            })
+        })
+    }
+
+    fn combine(&self, other: &Self, op: impl Fn(u64, u64) -> u64) -> Self {
+        let mut words = self.words;
+        for (word, other) in words.iter_mut().zip(&other.words) {
+            *word = op(*word, *other);
+        }
+        Self { words }
+    }
+}
+
+impl<const WORDS: usize> Default for BitSet<WORDS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const WORDS: usize> fmt::Debug for BitSet<WORDS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<const WORDS: usize> FromIterator<usize> for BitSet<WORDS> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<const WORDS: usize> Extend<usize> for BitSet<WORDS> {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<const WORDS: usize> BitAnd for BitSet<WORDS> {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.intersection(&other)
+    }
+}
+
+impl<const WORDS: usize> BitOr for BitSet<WORDS> {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(&other)
+    }
+}
+
+impl<const WORDS: usize> Sub for BitSet<WORDS> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.difference(&other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut set = BitSet128::new();
+        assert!(set.is_empty());
+        assert!(set.insert(0));
+        assert!(set.insert(64));
+        assert!(set.insert(127));
+        assert!(!set.insert(64));
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(127));
+        assert!(!set.contains(63));
+        assert!(!set.contains(128));
+        assert!(set.remove(0));
+        assert!(!set.remove(0));
+        assert_eq!(set.iter().collect::<Vec<_>>(), [64, 127]);
+        assert_eq!(format!("{set:?}"), "{64, 127}");
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: BitSet<1> = [1, 2, 3, 40].into_iter().collect();
+        let b: BitSet<1> = [2, 3, 4].into_iter().collect();
+        assert_eq!((a & b).iter().collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(a.intersection_len(&b), 2);
+        assert_eq!((a | b).iter().collect::<Vec<_>>(), [1, 2, 3, 4, 40]);
+        assert_eq!((a - b).iter().collect::<Vec<_>>(), [1, 40]);
+        assert!((a & b).is_subset(&a));
+        assert!(!a.is_subset(&b));
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit")]
+    fn test_insert_out_of_range() {
+        BitSet::<1>::new().insert(64);
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+
+/// Dense id of a string interned by an [`Interner`], usable as an index into a `Vec`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Id(u32);
+
+impl Id {
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Maps borrowed identifiers to dense [`Id`]s in the order they are first seen
+#[derive(Debug, Clone, Default)]
+pub struct Interner<'a> {
+    ids: HashMap<&'a str, Id>,
+    names: Vec<&'a str>,
+}
+
+impl<'a> Interner<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the id of `name`, assigning the next free id if it wasn't seen yet
+    pub fn intern(&mut self, name: &'a str) -> Id {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        let id = Id(self
+            .names
+            .len()
+            .try_into()
+            .expect("Too many interned names"));
+        self.ids.insert(name, id);
+        self.names.push(name);
+        id
+    }
+
+    pub fn get(&self, name: &str) -> Option<Id> {
+        self.ids.get(name).copied()
+    }
+
+    /// Get the name of an id. Panics if the id was created by another interner.
+    pub fn resolve(&self, id: Id) -> &'a str {
+        self.names[id.index()]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Order `entries` into a table which can be indexed by [`Id::index`].
+    ///
+    /// Fails if any interned name has no entry or more than one.
+    pub fn table<T>(&self, entries: impl IntoIterator<Item = (Id, T)>) -> Result<Vec<T>> {
+        let mut table: Vec<Option<T>> = (0..self.len()).map(|_| None).collect();
+        for (id, entry) in entries {
+            if table[id.index()].replace(entry).is_some() {
+                bail!("Duplicate entry for '{}'", self.resolve(id));
+            }
+        }
+        table
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry)| match entry {
+                Some(entry) => Ok(entry),
+                None => bail!("Missing entry for '{}'", self.names[i]),
+            })
+            .collect()
+    }
+
+    /// Iterate over all ids and their names, ordered by id
+    pub fn iter(&self) -> impl Iterator<Item = (Id, &'a str)> + '_ {
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (Id(i as u32), *name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_intern() {
+        let mut interner = Interner::new();
+        let a = interner.intern("AAA");
+        let b = interner.intern("BBB");
+        assert_eq!(interner.intern("AAA"), a);
+        assert_eq!((a.index(), b.index()), (0, 1));
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.get("BBB"), Some(b));
+        assert_eq!(interner.get("CCC"), None);
+        assert_eq!(interner.resolve(b), "BBB");
+        assert_eq!(
+            interner.iter().collect::<Vec<_>>(),
+            [(a, "AAA"), (b, "BBB")]
+        );
+    }
+
+    #[test]
+    fn test_table() {
+        let mut interner = Interner::new();
+        let a = interner.intern("a");
+        let b = interner.intern("b");
+        assert_eq!(interner.table([(b, 2), (a, 1)]).unwrap(), [1, 2]);
+        assert_eq!(
+            interner.table([(b, 2)]).unwrap_err().to_string(),
+            "Missing entry for 'a'"
+        );
+        assert_eq!(
+            interner
+                .table([(a, 1), (b, 2), (a, 3)])
+                .unwrap_err()
+                .to_string(),
+            "Duplicate entry for 'a'"
+        );
+    }
+
+    #[test]
+    fn test_intern_borrows_from_input() {
+        let input = String::from("in px qqz px");
+        let mut interner = Interner::new();
+        let ids: Vec<_> = input.split(' ').map(|s| interner.intern(s)).collect();
+        assert_eq!(ids[1], ids[3]);
+        assert!(std::ptr::eq(interner.resolve(ids[2]), &input[6..9]));
+    }
+}
@@ -0,0 +1,360 @@
+use std::ops::{Range, RangeInclusive};
+
+use anyhow::{Context, Error};
+use num_traits::PrimInt;
+
+/// Half-open interval `start..end`, which is empty if `start >= end`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    /// Create the interval `start..=last`.
+    ///
+    /// Panics if `last` is `T::MAX`, because the end of the interval wouldn't fit into `T`.
+    pub fn inclusive(start: T, last: T) -> Self {
+        Self::checked_inclusive(start, last).expect("End of the interval is too large")
+    }
+
+    /// Create the interval `start..=last`, or `None` if `last` is `T::MAX`
+    pub fn checked_inclusive(start: T, last: T) -> Option<Self> {
+        Some(Self::new(start, last.checked_add(&T::one())?))
+    }
+
+    /// Panics if `start + len` doesn't fit into `T`
+    pub fn from_len(start: T, len: T) -> Self {
+        Self::checked_from_len(start, len).expect("End of the interval is too large")
+    }
+
+    pub fn checked_from_len(start: T, len: T) -> Option<Self> {
+        Some(Self::new(start, start.checked_add(&len)?))
+    }
+
+    pub fn empty() -> Self {
+        Self::new(T::zero(), T::zero())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::zero()
+        } else {
+            self.end - self.start
+        }
+    }
+
+    /// The last value in the interval, if it isn't empty
+    pub fn last(&self) -> Option<T> {
+        (!self.is_empty()).then(|| self.end - T::one())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// Split into the values below `threshold` and the values greater or equal to `threshold`
+    pub fn split_at(&self, threshold: T) -> (Self, Self) {
+        let threshold = threshold.max(self.start).min(self.end.max(self.start));
+        (
+            Self::new(self.start, threshold),
+            Self::new(threshold, self.end),
+        )
+    }
+}
+
+impl<T> From<Range<T>> for Interval<T> {
+    fn from(range: Range<T>) -> Self {
+        Self {
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
+
+/// Fails if the range ends at `T::MAX`
+impl<T: PrimInt> TryFrom<RangeInclusive<T>> for Interval<T> {
+    type Error = Error;
+
+    fn try_from(range: RangeInclusive<T>) -> Result<Self, Error> {
+        Self::checked_inclusive(*range.start(), *range.end())
+            .context("The end of the range is too large for a half-open interval")
+    }
+}
+
+impl<T> From<Interval<T>> for Range<T> {
+    fn from(interval: Interval<T>) -> Self {
+        interval.start..interval.end
+    }
+}
+
+/// Set of values stored as sorted, non-overlapping and non-adjacent intervals
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The amount of values in the set
+    pub fn total_len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::zero(), |sum, interval| sum + interval.len())
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.intervals.last().and_then(|interval| interval.last())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self
+            .intervals
+            .partition_point(|interval| interval.end <= value);
+        self.intervals
+            .get(i)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn insert(&mut self, interval: impl Into<Interval<T>>) {
+        self.intervals.push(interval.into());
+        self.normalize();
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.intervals
+            .iter()
+            .chain(other.intervals.iter())
+            .copied()
+            .collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.intervals.get(i), other.intervals.get(j)) {
+            let intersection = a.intersection(b);
+            if !intersection.is_empty() {
+                intervals.push(intersection);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { intervals }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let mut others = other.intervals.iter().peekable();
+        for interval in &self.intervals {
+            let mut rest = *interval;
+            while let Some(o) = others.peek() {
+                if o.end <= rest.start {
+                    others.next();
+                    continue;
+                }
+                if o.start >= rest.end {
+                    break;
+                }
+                let (below, _) = rest.split_at(o.start);
+                if !below.is_empty() {
+                    intervals.push(below);
+                }
+                rest = rest.split_at(o.end).1;
+                if rest.is_empty() {
+                    break;
+                }
+            }
+            if !rest.is_empty() {
+                intervals.push(rest);
+            }
+        }
+        Self { intervals }
+    }
+
+    /// Split into the values below `threshold` and the values greater or equal to `threshold`
+    pub fn split_at(&self, threshold: T) -> (Self, Self) {
+        let (mut below, mut above) = (Vec::new(), Vec::new());
+        for interval in &self.intervals {
+            let (b, a) = interval.split_at(threshold);
+            if !b.is_empty() {
+                below.push(b);
+            }
+            if !a.is_empty() {
+                above.push(a);
+            }
+        }
+        (Self { intervals: below }, Self { intervals: above })
+    }
+
+    fn normalize(&mut self) {
+        self.intervals.retain(|interval| !interval.is_empty());
+        self.intervals.sort_by_key(|interval| interval.start);
+        let mut merged: Vec<Interval<T>> = Vec::with_capacity(self.intervals.len());
+        for interval in self.intervals.drain(..) {
+            match merged.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => merged.push(interval),
+            }
+        }
+        self.intervals = merged;
+    }
+}
+
+impl<T: PrimInt, I: Into<Interval<T>>> FromIterator<I> for IntervalSet<T> {
+    fn from_iter<It: IntoIterator<Item = I>>(iter: It) -> Self {
+        let mut set = Self {
+            intervals: iter.into_iter().map(Into::into).collect(),
+        };
+        set.normalize();
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn set(ranges: &[(i64, i64)]) -> IntervalSet<i64> {
+        ranges
+            .iter()
+            .map(|(start, end)| Interval::new(*start, *end))
+            .collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        let interval = Interval::new(3, 7);
+        assert_eq!(interval.len(), 4);
+        assert_eq!(interval.last(), Some(6));
+        assert!(interval.contains(3));
+        assert!(!interval.contains(7));
+        assert_eq!(
+            Interval::try_from(1..=4000).unwrap(),
+            Interval::new(1, 4001)
+        );
+        assert_eq!(Interval::from_len(79, 14), Interval::new(79, 93));
+        assert_eq!(Interval::new(5, 2).len(), 0);
+        assert_eq!(Interval::new(5, 2).last(), None);
+        assert_eq!(
+            interval.intersection(&Interval::new(5, 10)),
+            Interval::new(5, 7)
+        );
+        assert!(!interval.overlaps(&Interval::new(7, 10)));
+    }
+
+    #[test]
+    fn test_interval_split_at() {
+        let interval = Interval::new(3, 7);
+        assert_eq!(
+            interval.split_at(5),
+            (Interval::new(3, 5), Interval::new(5, 7))
+        );
+        assert_eq!(
+            interval.split_at(0),
+            (Interval::new(3, 3), Interval::new(3, 7))
+        );
+        assert_eq!(
+            interval.split_at(10),
+            (Interval::new(3, 7), Interval::new(7, 7))
+        );
+    }
+
+    #[test]
+    fn test_set_normalizes() {
+        let s = set(&[(5, 8), (1, 3), (3, 4), (10, 10), (7, 9)]);
+        assert_eq!(s.intervals(), [Interval::new(1, 4), Interval::new(5, 9)]);
+        assert_eq!(s.total_len(), 7);
+        assert_eq!(s.min(), Some(1));
+        assert_eq!(s.max(), Some(8));
+        assert!(s.contains(3));
+        assert!(!s.contains(4));
+        assert!(s.contains(8));
+        assert!(!s.contains(9));
+        assert!(IntervalSet::<i64>::new().is_empty());
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25), (28, 40)]);
+        assert_eq!(a.union(&b), set(&[(0, 40)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25), (28, 30)]));
+        assert_eq!(a.difference(&b), set(&[(0, 5), (25, 28)]));
+        assert_eq!(b.difference(&a), set(&[(10, 20), (30, 40)]));
+        assert_eq!(a.difference(&set(&[(0, 100)])), set(&[]));
+        assert_eq!(a.difference(&set(&[])), a);
+    }
+
+    #[test]
+    fn test_bounds() {
+        assert_eq!(
+            Interval::checked_inclusive(0, u64::MAX - 1),
+            Some(Interval::new(0, u64::MAX))
+        );
+        assert_eq!(Interval::checked_inclusive(0, u64::MAX), None);
+        assert!(Interval::try_from(0..=u64::MAX).is_err());
+        assert_eq!(
+            Interval::checked_from_len(u8::MAX - 3, 3),
+            Some(Interval::new(252, 255))
+        );
+        assert_eq!(Interval::checked_from_len(u8::MAX - 3, 4), None);
+        assert_eq!(Interval::new(0, u64::MAX).len(), u64::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "too large")]
+    fn test_inclusive_overflow() {
+        Interval::inclusive(0, i32::MAX);
+    }
+
+    #[test]
+    fn test_set_insert_and_split_at() {
+        let mut s = set(&[(0, 10)]);
+        s.insert(10..15);
+        assert_eq!(s, set(&[(0, 15)]));
+        assert_eq!(s.split_at(4), (set(&[(0, 4)]), set(&[(4, 15)])));
+    }
+}
@@ -6,6 +6,9 @@ use std::{
 
 use anyhow::{Context, Result};
 
+pub mod bitset;
+pub mod intern;
+pub mod interval;
 mod math;
 pub mod parsing;
 
@@ -1,4 +1,4 @@
-use num_traits::{CheckedMul, Num, Signed};
+use num_traits::{CheckedMul, Num, PrimInt, Signed};
 
 /// Compute the greatest common denominator
 pub fn gcd<T>(a: T, b: T) -> T
@@ -19,3 +19,26 @@ where
 {
     (a / gcd(a, b)).checked_mul(&b).expect("Number overflow")
 }
+
+/// Compute the integer square root, which is the largest `r` with `r * r <= n`.
+///
+/// Panics if `n` is negative.
+pub fn isqrt<T: PrimInt>(n: T) -> T {
+    assert!(
+        n >= T::zero(),
+        "Cannot take the square root of a negative number"
+    );
+    if n < T::one() + T::one() {
+        return n;
+    }
+    let bits = T::zero().count_zeros() - n.leading_zeros();
+    // Newton's method converges monotonically from any start above the root
+    let mut root = T::one() << bits.div_ceil(2) as usize;
+    loop {
+        let next = (root + n / root) >> 1;
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
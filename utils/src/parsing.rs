@@ -1,23 +1,86 @@
-use std::{fmt::Debug, str::FromStr};
+use std::{
+    any::type_name,
+    fmt::{self, Debug, Display},
+    num::{IntErrorKind, ParseIntError},
+    str::FromStr,
+};
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use nom::{
-    bytes::complete::tag,
-    character::complete::{digit1, multispace0, multispace1, space0, space1},
-    combinator::{map_res, opt, recognize},
-    error::ParseError,
-    multi::{separated_list0, separated_list1},
-    sequence::{delimited, preceded, tuple},
+    branch::alt,
+    bytes::complete::{tag, take_till1},
+    character::complete::{
+        char, digit1, hex_digit1, line_ending, multispace0, multispace1, not_line_ending, one_of,
+        space0, space1,
+    },
+    combinator::{map, map_res, opt, recognize, verify},
+    error::{ErrorKind as NomErrorKind, FromExternalError, ParseError},
+    multi::{many0, many1, separated_list0, separated_list1},
+    sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
     IResult, Parser,
 };
+use num_traits::Num;
+
+/// The error type of all parsers in this module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error<I> {
+    pub input: I,
+    pub kind: ErrorKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Nom(NomErrorKind),
+    NumberTooLarge { type_name: &'static str },
+    NumberTooSmall { type_name: &'static str },
+}
+
+impl<I> Error<I> {
+    pub fn new(input: I, kind: ErrorKind) -> Self {
+        Self { input, kind }
+    }
+}
+
+impl<I> ParseError<I> for Error<I> {
+    fn from_error_kind(input: I, kind: NomErrorKind) -> Self {
+        Self::new(input, ErrorKind::Nom(kind))
+    }
+
+    fn append(_input: I, _kind: NomErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+impl<I, E> FromExternalError<I, E> for Error<I> {
+    fn from_external_error(input: I, kind: NomErrorKind, _e: E) -> Self {
+        Self::from_error_kind(input, kind)
+    }
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nom(kind) => write!(f, "{}", kind.description()),
+            Self::NumberTooLarge { type_name } => write!(f, "number too large for {type_name}"),
+            Self::NumberTooSmall { type_name } => write!(f, "number too small for {type_name}"),
+        }
+    }
+}
 
 pub fn parse_with_nom<'a, 'b, P, T>(input: &'a str, parse: P) -> Result<T>
 where
-    P: FnOnce(&'a str) -> IResult<&'b str, T>,
+    P: FnOnce(&'a str) -> IResult<&'b str, T, Error<&'b str>>,
     T: Debug,
 {
     let (_, parsed) = match parse(input) {
         Ok(parsed) => parsed,
+        Err(nom::Err::Error(err) | nom::Err::Failure(err)) => {
+            let (line, column) = line_and_column(input, err.input);
+            bail!(
+                "Failed to parse input: {} at line {line}, column {column}",
+                err.kind
+            )
+        }
         Err(err) => {
             bail!("Failed to parse input: {err}")
         }
@@ -25,10 +88,293 @@ where
     Ok(parsed)
 }
 
-pub fn number<T: FromStr>(input: &str) -> IResult<&str, T> {
+/// Get the 1-based line and column at which `rest` starts within `input`
+fn line_and_column(input: &str, rest: &str) -> (usize, usize) {
+    let offset = input.len().saturating_sub(rest.len());
+    let consumed = &input[..offset];
+    let line = consumed.matches('\n').count() + 1;
+    let column = match consumed.rfind('\n') {
+        Some(i) => offset - i,
+        None => offset + 1,
+    };
+    (line, column)
+}
+
+pub fn number<T: FromStr>(input: &str) -> IResult<&str, T, Error<&str>> {
     map_res(recognize(tuple((opt(tag("-")), digit1))), T::from_str)(input)
 }
 
+/// Parse a number without any sign
+pub fn unsigned_number<T: FromStr>(input: &str) -> IResult<&str, T, Error<&str>> {
+    map_res(digit1, T::from_str)(input)
+}
+
+/// Parse a number with an optional `+` or `-` sign
+pub fn signed_number<T: FromStr>(input: &str) -> IResult<&str, T, Error<&str>> {
+    map_res(recognize(pair(opt(one_of("+-")), digit1)), T::from_str)(input)
+}
+
+/// Parse a hexadecimal number like `0xff` or `-0x1A`
+pub fn hex_number<T: Num>(input: &str) -> IResult<&str, T, Error<&str>> {
+    radix_number(alt((tag("0x"), tag("0X"))), hex_digit1, 16)(input)
+}
+
+/// Parse a binary number like `0b1010` or `-0b1`
+pub fn binary_number<T: Num>(input: &str) -> IResult<&str, T, Error<&str>> {
+    radix_number(
+        alt((tag("0b"), tag("0B"))),
+        recognize(many1(one_of("01"))),
+        2,
+    )(input)
+}
+
+fn radix_number<'a, T, P, D>(
+    prefix: P,
+    digits: D,
+    radix: u32,
+) -> impl FnMut(&'a str) -> IResult<&'a str, T, Error<&'a str>>
+where
+    T: Num,
+    P: Parser<&'a str, &'a str, Error<&'a str>>,
+    D: Parser<&'a str, &'a str, Error<&'a str>>,
+{
+    map_res(
+        tuple((opt(one_of("+-")), prefix, digits)),
+        move |(sign, _, digits)| match sign {
+            Some('-') => T::from_str_radix(&format!("-{digits}"), radix),
+            _ => T::from_str_radix(digits, radix),
+        },
+    )
+}
+
+/// Parse a number whose digits are grouped by `separator`, like `1_000_000` or `-1,024`
+pub fn separated_number<'a, T: FromStr>(
+    separator: char,
+) -> impl FnMut(&'a str) -> IResult<&'a str, T, Error<&'a str>> {
+    map_res(
+        recognize(tuple((
+            opt(one_of("+-")),
+            digit1,
+            many0(pair(char(separator), digit1)),
+        ))),
+        move |s: &str| s.replace(separator, "").parse::<T>(),
+    )
+}
+
+/// Like [`signed_number`], but fails with [`ErrorKind::NumberTooLarge`] or
+/// [`ErrorKind::NumberTooSmall`] if the number doesn't fit into `T`.
+///
+/// The failure cannot be recovered by combinators like `alt`.
+pub fn checked_number<T>(input: &str) -> IResult<&str, T, Error<&str>>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    let (rest, digits) = recognize(pair(opt(one_of("+-")), digit1))(input)?;
+    match digits.parse() {
+        Ok(n) => Ok((rest, n)),
+        Err(err) => {
+            let type_name = type_name::<T>();
+            let kind = match err.kind() {
+                IntErrorKind::PosOverflow => ErrorKind::NumberTooLarge { type_name },
+                IntErrorKind::NegOverflow => ErrorKind::NumberTooSmall { type_name },
+                _ => {
+                    return Err(nom::Err::Error(Error::from_error_kind(
+                        input,
+                        NomErrorKind::Digit,
+                    )))
+                }
+            };
+            Err(nom::Err::Failure(Error::new(input, kind)))
+        }
+    }
+}
+
+/// How the numbers in a row of a [`Table`] are read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowNumbers {
+    /// Every whitespace separated number is a value in its own column
+    Separate,
+    /// All digits of the row form a single number, ignoring the whitespace in between.
+    /// A leading `-` applies to the whole number.
+    Joined,
+}
+
+/// Numbers from labelled rows like `Time:  7  15`, stored by column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table<'a, T> {
+    labels: Vec<&'a str>,
+    columns: Vec<Vec<T>>,
+}
+
+impl<'a, T> Table<'a, T> {
+    pub fn labels(&self) -> &[&'a str] {
+        &self.labels
+    }
+
+    /// Each column contains one value per label, in the order of [`Table::labels`]
+    pub fn columns(&self) -> &[Vec<T>] {
+        &self.columns
+    }
+
+    pub fn position(&self, label: &str) -> Option<usize> {
+        self.labels.iter().position(|l| *l == label)
+    }
+}
+
+/// Parse lines of the form `<label>: <numbers>` into a column-oriented [`Table`].
+///
+/// All rows need to contain the same amount of numbers.
+pub fn labelled_table<'a, T: FromStr>(
+    row_numbers: RowNumbers,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Table<'a, T>, Error<&'a str>> {
+    let parse_numbers = move |input: &'a str| match row_numbers {
+        RowNumbers::Separate => separated_list1(space1, number)(input),
+        RowNumbers::Joined => map_res(
+            recognize(pair(opt(tag("-")), separated_list1(space1, digit1))),
+            |s: &str| {
+                s.split_whitespace()
+                    .collect::<String>()
+                    .parse()
+                    .map(|n| vec![n])
+            },
+        )(input),
+    };
+    let parse_row = tuple((
+        take_till1(|c| c == ':' || c == '\n'),
+        preceded(pair(tag(":"), space0), parse_numbers),
+    ));
+    map(
+        verify(
+            separated_list1(newline, parse_row),
+            |rows: &Vec<(&str, Vec<T>)>| rows.iter().all(|(_, n)| n.len() == rows[0].1.len()),
+        ),
+        |rows| {
+            let n_columns = rows[0].1.len();
+            let mut columns: Vec<Vec<T>> = (0..n_columns).map(|_| Vec::new()).collect();
+            let mut labels = Vec::new();
+            for (label, numbers) in rows {
+                labels.push(label.trim());
+                for (column, n) in columns.iter_mut().zip(numbers) {
+                    column.push(n);
+                }
+            }
+            Table { labels, columns }
+        },
+    )
+}
+
+/// Parse the end of a line (`\n` or `\r\n`), including trailing spaces.
+///
+/// Indentation of the following line is not consumed.
+pub fn newline<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, &'a str, E> {
+    recognize(pair(space0, line_ending))(input)
+}
+
+/// Parse a line which only contains whitespace or a comment starting with `comment_prefix`,
+/// including its line ending
+pub fn skipped_line<'a>(
+    comment_prefix: Option<&'a str>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str, Error<&'a str>> {
+    move |input| {
+        let comment = |input| match comment_prefix {
+            Some(prefix) => recognize(pair(tag(prefix), not_line_ending))(input),
+            None => Err(nom::Err::Error(Error::from_error_kind(
+                input,
+                NomErrorKind::Tag,
+            ))),
+        };
+        recognize(tuple((space0, opt(comment), line_ending)))(input)
+    }
+}
+
+/// Like [`l1_newline`], but skips blank lines and lines starting with `comment_prefix`
+/// before, between and after the parsed lines
+pub fn l1_newline_skipping<'a, O, P>(
+    comment_prefix: Option<&'a str>,
+    parser: P,
+) -> impl Parser<&'a str, Vec<O>, Error<&'a str>>
+where
+    P: Parser<&'a str, O, Error<&'a str>>,
+{
+    delimited(
+        many0(skipped_line(comment_prefix)),
+        separated_list1(pair(newline, many0(skipped_line(comment_prefix))), parser),
+        pair(opt(newline), many0(skipped_line(comment_prefix))),
+    )
+}
+
+/// Parse one or more blank lines separating two blocks of lines
+pub fn blank_lines(input: &str) -> IResult<&str, &str, Error<&str>> {
+    recognize(pair(line_ending, many1(pair(space0, line_ending))))(input)
+}
+
+/// Parse blocks of lines which are separated by blank lines
+pub fn sections<'a, O, P>(parser: P) -> impl Parser<&'a str, Vec<O>, Error<&'a str>>
+where
+    P: Parser<&'a str, O, Error<&'a str>>,
+{
+    separated_list1(blank_lines, parser)
+}
+
+/// Parse a header line followed by one or more body lines
+pub fn header_section<'a, H, B, PH, PB>(
+    header: PH,
+    body_line: PB,
+) -> impl Parser<&'a str, (H, Vec<B>), Error<&'a str>>
+where
+    PH: Parser<&'a str, H, Error<&'a str>>,
+    PB: Parser<&'a str, B, Error<&'a str>>,
+{
+    separated_pair(header, newline, separated_list1(newline, body_line))
+}
+
+/// Parse a section titled `<name>:`. The content may start on the same line or on the next one.
+pub fn named_section<'a, O, P>(content: P) -> impl Parser<&'a str, (&'a str, O), Error<&'a str>>
+where
+    P: Parser<&'a str, O, Error<&'a str>>,
+{
+    pair(
+        terminated(take_till1(|c| c == ':' || c == '\n'), tag(":")),
+        preceded(pair(space0, opt(line_ending)), content),
+    )
+}
+
+/// Parse blank line separated [`named_section`]s, which can be looked up by their name
+pub fn named_sections<'a, O, P>(
+    content: P,
+) -> impl Parser<&'a str, NamedSections<'a, O>, Error<&'a str>>
+where
+    P: Parser<&'a str, O, Error<&'a str>>,
+{
+    map(sections(named_section(content)), |sections| NamedSections {
+        sections,
+    })
+}
+
+/// The parsed content of sections by their name, in input order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedSections<'a, O> {
+    sections: Vec<(&'a str, O)>,
+}
+
+impl<'a, O> NamedSections<'a, O> {
+    pub fn get(&self, name: &str) -> Result<&O> {
+        self.sections
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, content)| content)
+            .with_context(|| format!("Missing section '{name}'"))
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.sections.iter().map(|(name, _)| *name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &O)> {
+        self.sections.iter().map(|(name, content)| (*name, content))
+    }
+}
+
 macro_rules! create_delimited2_parser {
     ($name:ident, $delimiter1:expr, $delimiter2:expr) => {
         pub fn $name<'a, O, E, P>(parser: P) -> impl Parser<&'a str, O, E>
@@ -73,8 +419,8 @@ create_seperated_list_parser!(l0_comma, separated_list0, d_space0(tag(",")));
 create_seperated_list_parser!(l1_comma, separated_list1, d_space0(tag(",")));
 create_seperated_list_parser!(l0_semi, separated_list0, d_space0(tag(";")));
 create_seperated_list_parser!(l1_semi, separated_list1, d_space0(tag(";")));
-create_seperated_list_parser!(l0_newline, separated_list0, d_space0(tag("\n")));
-create_seperated_list_parser!(l1_newline, separated_list1, d_space0(tag("\n")));
+create_seperated_list_parser!(l0_newline, separated_list0, newline);
+create_seperated_list_parser!(l1_newline, separated_list1, newline);
 
 macro_rules! create_preceeded_parser {
     ($name:ident, $token:expr) => {
@@ -93,3 +439,251 @@ create_preceeded_parser!(p_column, tag(":"));
 create_preceeded_parser!(p_eq, tag("="));
 create_preceeded_parser!(p_space, space0);
 create_preceeded_parser!(p_mspace, multispace0);
+
+#[cfg(test)]
+mod tests {
+    use nom::{character::complete::alpha1, combinator::all_consuming};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn word(input: &str) -> IResult<&str, &str, Error<&str>> {
+        alpha1(input)
+    }
+
+    #[test]
+    fn test_number() {
+        assert_eq!(number::<i32>("42 rest"), Ok((" rest", 42)));
+        assert_eq!(number::<i32>("-42"), Ok(("", -42)));
+        assert!(number::<i32>("+42").is_err());
+    }
+
+    #[test]
+    fn test_unsigned_number() {
+        assert_eq!(unsigned_number::<u8>("255"), Ok(("", 255)));
+        assert!(unsigned_number::<u8>("256").is_err());
+        assert!(unsigned_number::<i32>("-1").is_err());
+        assert!(unsigned_number::<i32>("+1").is_err());
+    }
+
+    #[test]
+    fn test_signed_number() {
+        assert_eq!(signed_number::<i32>("+7"), Ok(("", 7)));
+        assert_eq!(signed_number::<i32>("-7"), Ok(("", -7)));
+        assert_eq!(signed_number::<i32>("7"), Ok(("", 7)));
+        assert!(signed_number::<i32>("+-7").is_err());
+        assert!(signed_number::<i32>("-").is_err());
+    }
+
+    #[test]
+    fn test_hex_number() {
+        assert_eq!(hex_number::<i32>("0xff"), Ok(("", 255)));
+        assert_eq!(hex_number::<i32>("0X1a,"), Ok((",", 26)));
+        assert_eq!(hex_number::<i32>("-0x10"), Ok(("", -16)));
+        assert_eq!(hex_number::<i32>("-0x80000000"), Ok(("", i32::MIN)));
+        assert!(hex_number::<i32>("0x80000000").is_err());
+        assert!(hex_number::<i32>("ff").is_err());
+        assert!(hex_number::<u8>("-0x1").is_err());
+    }
+
+    #[test]
+    fn test_binary_number() {
+        assert_eq!(binary_number::<u8>("0b1010"), Ok(("", 10)));
+        assert_eq!(binary_number::<u8>("0b12"), Ok(("2", 1)));
+        assert_eq!(binary_number::<i8>("-0b10000000"), Ok(("", i8::MIN)));
+        assert!(binary_number::<u8>("0b").is_err());
+        assert!(binary_number::<u8>("0b100000000").is_err());
+    }
+
+    #[test]
+    fn test_separated_number() {
+        assert_eq!(
+            separated_number::<i64>('_')("1_000_000"),
+            Ok(("", 1_000_000))
+        );
+        assert_eq!(separated_number::<i64>(',')("-1,024"), Ok(("", -1024)));
+        assert_eq!(separated_number::<i64>('_')("12_"), Ok(("_", 12)));
+        assert_eq!(separated_number::<i64>(' ')("7  15"), Ok(("  15", 7)));
+        assert!(separated_number::<i64>('_')("_1").is_err());
+    }
+
+    #[test]
+    fn test_checked_number() {
+        assert_eq!(checked_number::<i32>("2147483647"), Ok(("", i32::MAX)));
+        assert_eq!(checked_number::<i32>("-2147483648"), Ok(("", i32::MIN)));
+        assert_eq!(
+            checked_number::<i32>("2147483648"),
+            Err(nom::Err::Failure(Error::new(
+                "2147483648",
+                ErrorKind::NumberTooLarge { type_name: "i32" }
+            )))
+        );
+        assert_eq!(
+            checked_number::<i32>("-2147483649"),
+            Err(nom::Err::Failure(Error::new(
+                "-2147483649",
+                ErrorKind::NumberTooSmall { type_name: "i32" }
+            )))
+        );
+        assert!(matches!(
+            checked_number::<u32>("-1"),
+            Err(nom::Err::Error(_))
+        ));
+        assert!(matches!(
+            checked_number::<u32>("x"),
+            Err(nom::Err::Error(_))
+        ));
+    }
+
+    #[test]
+    fn test_labelled_table_separate() {
+        let input = "Time:      7  15   30\nDistance:  9  40  200";
+        let (rest, table) = labelled_table::<i64>(RowNumbers::Separate)(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(table.labels(), ["Time", "Distance"]);
+        assert_eq!(table.columns(), [vec![7, 9], vec![15, 40], vec![30, 200]]);
+        assert_eq!(table.position("Distance"), Some(1));
+        assert_eq!(table.position("Speed"), None);
+    }
+
+    #[test]
+    fn test_labelled_table_joined() {
+        let input = "Time:      7  15   30\nDistance:  9  40  200";
+        let (_, table) = labelled_table::<i64>(RowNumbers::Joined)(input).unwrap();
+        assert_eq!(table.columns(), [vec![71530, 940200]]);
+    }
+
+    #[test]
+    fn test_labelled_table_negative_numbers() {
+        let input = "Low: -7 15\nHigh: 9 -40";
+        let (_, table) = labelled_table::<i64>(RowNumbers::Separate)(input).unwrap();
+        assert_eq!(table.columns(), [vec![-7, 9], vec![15, -40]]);
+        let input = "Low: -7 15\nHigh: 9 40";
+        let (_, table) = labelled_table::<i64>(RowNumbers::Joined)(input).unwrap();
+        assert_eq!(table.columns(), [vec![-715, 940]]);
+        // A sign inside a joined number is not a digit
+        assert!(labelled_table::<i64>(RowNumbers::Joined)("High: 9 -40")
+            .is_ok_and(|(rest, _)| rest == " -40"));
+    }
+
+    #[test]
+    fn test_labelled_table_uneven_rows() {
+        let input = "Time: 7 15\nDistance: 9";
+        assert!(labelled_table::<i64>(RowNumbers::Separate)(input).is_err());
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = "a\nb\n\nc\n  \n\nd";
+        let (rest, blocks) = sections(l1_newline(alpha1)).parse(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(blocks, [vec!["a", "b"], vec!["c"], vec!["d"]]);
+    }
+
+    #[test]
+    fn test_header_section() {
+        let input = "numbers\n1 2\n3 4\n\nrest";
+        let (rest, (header, body)) = header_section(alpha1, separated_list1(space1, number::<i32>))
+            .parse(input)
+            .unwrap();
+        assert_eq!(rest, "\n\nrest");
+        assert_eq!(header, "numbers");
+        assert_eq!(body, [vec![1, 2], vec![3, 4]]);
+    }
+
+    #[test]
+    fn test_named_sections() {
+        let input = "seeds: 1 2\n\nseed-to-soil map:\n3 4\n5 6";
+        let parse_rows = separated_list1(line_ending, separated_list1(space1, number::<i32>));
+        let (rest, sections) = named_sections(parse_rows).parse(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            sections.names().collect::<Vec<_>>(),
+            ["seeds", "seed-to-soil map"]
+        );
+        assert_eq!(sections.get("seeds").unwrap(), &[vec![1, 2]]);
+        assert_eq!(
+            sections.get("seed-to-soil map").unwrap(),
+            &[vec![3, 4], vec![5, 6]]
+        );
+        assert_eq!(
+            sections.get("soil-to-water map").unwrap_err().to_string(),
+            "Missing section 'soil-to-water map'"
+        );
+    }
+
+    #[test]
+    fn test_l1_newline_keeps_indentation() {
+        let input = "a  \n  b\nc";
+        let (rest, lines) = l1_newline(recognize(pair(space0, word)))
+            .parse(input)
+            .unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(lines, ["a", "  b", "c"]);
+
+        let (rest, lines) = l1_newline(word).parse(input).unwrap();
+        assert_eq!(rest, "  \n  b\nc");
+        assert_eq!(lines, ["a"]);
+    }
+
+    #[test]
+    fn test_l0_newline_empty() {
+        let (rest, lines) = l0_newline(word).parse("").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(lines, Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_l1_newline_crlf() {
+        let input = "1 2\r\n3 4\r\n5 6";
+        let (rest, lines) = l1_newline(separated_list1(space1, number::<i32>))
+            .parse(input)
+            .unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(lines, [vec![1, 2], vec![3, 4], vec![5, 6]]);
+    }
+
+    #[test]
+    fn test_l1_newline_skipping() {
+        let input = "# header\n\n1\n  # comment\n2\r\n   \r\n3\n# footer\n";
+        let (rest, numbers) = l1_newline_skipping(Some("#"), number::<i32>)
+            .parse(input)
+            .unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(numbers, [1, 2, 3]);
+
+        let (rest, numbers) = l1_newline_skipping(None, number::<i32>)
+            .parse("1\n\n2\n#3")
+            .unwrap();
+        assert_eq!(rest, "#3");
+        assert_eq!(numbers, [1, 2]);
+    }
+
+    #[test]
+    fn test_crlf_sections_and_table() {
+        let input = "Time:  7  15\r\nDistance:  9  40\r\n\r\nseeds: 1 2\r\n\r\nmap:\r\n3 4\r\n";
+        let (rest, table) = labelled_table::<i64>(RowNumbers::Separate)(input).unwrap();
+        assert_eq!(table.columns(), [vec![7, 9], vec![15, 40]]);
+
+        let (rest, _) = blank_lines(rest).unwrap();
+        let parse_rows = l1_newline(separated_list1(space1, number::<i32>));
+        let (rest, sections) = named_sections(parse_rows).parse(rest).unwrap();
+        assert_eq!(rest, "\r\n");
+        assert_eq!(sections.get("seeds").unwrap(), &[vec![1, 2]]);
+        assert_eq!(sections.get("map").unwrap(), &[vec![3, 4]]);
+    }
+
+    #[test]
+    fn test_checked_number_error_message() {
+        let input = "1\n2\n  99999999999";
+        let err = parse_with_nom(
+            input,
+            all_consuming(l1_newline(p_space(checked_number::<i32>))),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to parse input: number too large for i32 at line 3, column 3"
+        );
+    }
+}